@@ -0,0 +1,76 @@
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+use rayon::prelude::*;
+
+/// Parallel fold with a separate per-chunk accumulator and cross-chunk combiner.
+///
+/// Unlike `parallel_reduce`, which assumes the reducer doubles as both the
+/// per-chunk accumulator and the combiner, `parallel_fold` mirrors rayon's
+/// fold/reduce split: each `par_chunks` worker starts from its own clone of
+/// `identity` and applies `fold_op(acc, item)` across its chunk, then the
+/// resulting partial accumulators are merged pairwise with `combine_op(a, b)`.
+/// `identity` is cloned per chunk rather than shared mutably, so `fold_op` can
+/// safely mutate-and-return a running accumulator whose type differs from the
+/// element type (e.g. folding ints into a running dict or set). `combine_op`
+/// must be associative for the result to be deterministic. An empty iterable
+/// returns `identity` unchanged.
+#[pyfunction]
+pub fn parallel_fold(
+    fold_op: Bound<PyAny>,
+    combine_op: Bound<PyAny>,
+    identity: Bound<PyAny>,
+    iterable: Bound<PyAny>,
+    chunk_size: Option<usize>,
+) -> PyResult<PyObject> {
+    // Convert to PyObjects to avoid Sync issues
+    let items: Vec<PyObject> = iterable.try_iter()?.map(|item| item.map(|i| i.into())).collect::<PyResult<Vec<_>>>()?;
+
+    if items.is_empty() {
+        return Ok(identity.into());
+    }
+
+    let chunk_size = chunk_size.unwrap_or_else(|| {
+        let len = items.len();
+        if len < 1000 {
+            len / rayon::current_num_threads().max(1)
+        } else {
+            1000
+        }
+    });
+
+    let fold_op: PyObject = fold_op.into();
+    let combine_op: PyObject = combine_op.into();
+    let identity: PyObject = identity.into();
+
+    // Fold within each chunk, starting from a fresh clone of `identity`
+    let partials: Vec<PyObject> = items
+        .par_chunks(chunk_size.max(1))
+        .map(|chunk| {
+            Python::with_gil(|py| {
+                let bound_fold_op = fold_op.bind(py);
+                let mut acc = identity.clone_ref(py);
+
+                for item in chunk {
+                    let bound_item = item.bind(py);
+                    acc = bound_fold_op.call1((acc, bound_item))?.into();
+                }
+
+                Ok(acc)
+            })
+        })
+        .collect::<PyResult<Vec<PyObject>>>()?;
+
+    // Then combine the partial accumulators pairwise
+    Python::with_gil(|py| {
+        let bound_combine_op = combine_op.bind(py);
+        let mut partials = partials.into_iter();
+        let mut result = partials.next().unwrap();
+
+        for partial in partials {
+            let bound_partial = partial.bind(py);
+            result = bound_combine_op.call1((result, bound_partial))?.into();
+        }
+
+        Ok(result)
+    })
+}
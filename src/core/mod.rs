@@ -1,7 +1,11 @@
 pub mod filter;
+pub mod fold;
 pub mod map;
 pub mod reduce;
+pub mod search;
 
 pub use filter::*;
+pub use fold::*;
 pub use map::*;
 pub use reduce::*;
+pub use search::*;
@@ -0,0 +1,207 @@
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Short-circuiting parallel `any`: returns `True` as soon as any chunk finds an
+/// item for which `predicate` is truthy, without waiting for the other chunks.
+#[pyfunction]
+pub fn parallel_any(
+    predicate: Bound<PyAny>,
+    iterable: Bound<PyAny>,
+    chunk_size: Option<usize>,
+) -> PyResult<bool> {
+    // Convert to PyObjects to avoid Sync issues
+    let items: Vec<PyObject> = iterable.try_iter()?.map(|item| item.map(|i| i.into())).collect::<PyResult<Vec<_>>>()?;
+
+    if items.is_empty() {
+        return Ok(false);
+    }
+
+    let chunk_size = chunk_size.unwrap_or_else(|| {
+        let len = items.len();
+        if len < 1000 {
+            len / rayon::current_num_threads().max(1)
+        } else {
+            1000
+        }
+    });
+
+    let predicate: PyObject = predicate.into();
+    let found = AtomicBool::new(false);
+
+    items
+        .par_chunks(chunk_size.max(1))
+        .try_for_each(|chunk| -> PyResult<()> {
+            Python::with_gil(|py| {
+                let bound_predicate = predicate.bind(py);
+                for item in chunk {
+                    if found.load(Ordering::Relaxed) {
+                        return Ok(());
+                    }
+                    let bound_item = item.bind(py);
+                    if bound_predicate.call1((bound_item,))?.is_truthy()? {
+                        found.store(true, Ordering::Relaxed);
+                        return Ok(());
+                    }
+                }
+                Ok(())
+            })
+        })?;
+
+    Ok(found.load(Ordering::Relaxed))
+}
+
+/// Short-circuiting parallel `all`: returns `False` as soon as any chunk finds an
+/// item for which `predicate` is falsey, without waiting for the other chunks.
+#[pyfunction]
+pub fn parallel_all(
+    predicate: Bound<PyAny>,
+    iterable: Bound<PyAny>,
+    chunk_size: Option<usize>,
+) -> PyResult<bool> {
+    let items: Vec<PyObject> = iterable.try_iter()?.map(|item| item.map(|i| i.into())).collect::<PyResult<Vec<_>>>()?;
+
+    if items.is_empty() {
+        return Ok(true);
+    }
+
+    let chunk_size = chunk_size.unwrap_or_else(|| {
+        let len = items.len();
+        if len < 1000 {
+            len / rayon::current_num_threads().max(1)
+        } else {
+            1000
+        }
+    });
+
+    let predicate: PyObject = predicate.into();
+    let failed = AtomicBool::new(false);
+
+    items
+        .par_chunks(chunk_size.max(1))
+        .try_for_each(|chunk| -> PyResult<()> {
+            Python::with_gil(|py| {
+                let bound_predicate = predicate.bind(py);
+                for item in chunk {
+                    if failed.load(Ordering::Relaxed) {
+                        return Ok(());
+                    }
+                    let bound_item = item.bind(py);
+                    if !bound_predicate.call1((bound_item,))?.is_truthy()? {
+                        failed.store(true, Ordering::Relaxed);
+                        return Ok(());
+                    }
+                }
+                Ok(())
+            })
+        })?;
+
+    Ok(!failed.load(Ordering::Relaxed))
+}
+
+/// Short-circuiting parallel search that returns any element matching `predicate`,
+/// with no ordering guarantees: workers bail the instant another worker reports a hit.
+#[pyfunction]
+pub fn parallel_find_any(
+    predicate: Bound<PyAny>,
+    iterable: Bound<PyAny>,
+    chunk_size: Option<usize>,
+) -> PyResult<Option<PyObject>> {
+    let items: Vec<PyObject> = iterable.try_iter()?.map(|item| item.map(|i| i.into())).collect::<PyResult<Vec<_>>>()?;
+
+    if items.is_empty() {
+        return Ok(None);
+    }
+
+    let chunk_size = chunk_size.unwrap_or_else(|| {
+        let len = items.len();
+        if len < 1000 {
+            len / rayon::current_num_threads().max(1)
+        } else {
+            1000
+        }
+    });
+
+    let predicate: PyObject = predicate.into();
+    let found = AtomicBool::new(false);
+
+    let results: Vec<Option<PyObject>> = items
+        .par_chunks(chunk_size.max(1))
+        .map(|chunk| -> PyResult<Option<PyObject>> {
+            Python::with_gil(|py| {
+                let bound_predicate = predicate.bind(py);
+                for item in chunk {
+                    if found.load(Ordering::Relaxed) {
+                        return Ok(None);
+                    }
+                    let bound_item = item.bind(py);
+                    if bound_predicate.call1((bound_item,))?.is_truthy()? {
+                        found.store(true, Ordering::Relaxed);
+                        return Ok(Some(item.clone_ref(py)));
+                    }
+                }
+                Ok(None)
+            })
+        })
+        .collect::<PyResult<Vec<Option<PyObject>>>>()?;
+
+    Ok(results.into_iter().flatten().next())
+}
+
+/// Parallel search that returns the lowest-index element matching `predicate`.
+/// Each worker tracks the global best index seen so far via `fetch_min` and stops
+/// scanning once its own position has passed that index, since no later match it
+/// could report would win anyway.
+#[pyfunction]
+pub fn parallel_find_first(
+    predicate: Bound<PyAny>,
+    iterable: Bound<PyAny>,
+    chunk_size: Option<usize>,
+) -> PyResult<Option<PyObject>> {
+    let items: Vec<PyObject> = iterable.try_iter()?.map(|item| item.map(|i| i.into())).collect::<PyResult<Vec<_>>>()?;
+
+    if items.is_empty() {
+        return Ok(None);
+    }
+
+    let chunk_size = chunk_size.unwrap_or_else(|| {
+        let len = items.len();
+        if len < 1000 {
+            len / rayon::current_num_threads().max(1)
+        } else {
+            1000
+        }
+    });
+
+    let predicate: PyObject = predicate.into();
+    let best_index = AtomicUsize::new(usize::MAX);
+
+    items
+        .par_chunks(chunk_size.max(1))
+        .enumerate()
+        .try_for_each(|(chunk_idx, chunk)| -> PyResult<()> {
+            Python::with_gil(|py| {
+                let bound_predicate = predicate.bind(py);
+                for (offset, item) in chunk.iter().enumerate() {
+                    let global_index = chunk_idx * chunk_size.max(1) + offset;
+                    if global_index >= best_index.load(Ordering::Relaxed) {
+                        return Ok(());
+                    }
+                    let bound_item = item.bind(py);
+                    if bound_predicate.call1((bound_item,))?.is_truthy()? {
+                        best_index.fetch_min(global_index, Ordering::Relaxed);
+                        return Ok(());
+                    }
+                }
+                Ok(())
+            })
+        })?;
+
+    let best_index = best_index.load(Ordering::Relaxed);
+    if best_index == usize::MAX {
+        Ok(None)
+    } else {
+        Python::with_gil(|py| Ok(Some(items[best_index].clone_ref(py))))
+    }
+}
@@ -162,6 +162,19 @@ impl ClusterManager {
         }
         Ok(())
     }
+
+    /// Observed-throughput weight per active node (more CPU cores and lower load
+    /// means a larger weight), used to seed the work-stealing scheduler's initial
+    /// deques proportionally instead of splitting work evenly.
+    pub fn get_node_weights(&self) -> PyResult<HashMap<String, f64>> {
+        let nodes = self.nodes.lock().unwrap();
+        let weights = nodes
+            .values()
+            .filter(|node| matches!(node.status, NodeStatus::Active))
+            .map(|node| (node.id.clone(), node.capabilities.cpu_cores as f64 / (node.load + 0.1)))
+            .collect();
+        Ok(weights)
+    }
 }
 
 /// Load balancer for distributing tasks across nodes
@@ -0,0 +1,103 @@
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Serialization format used to move task payloads and results to/from bytes.
+/// `pickle` is the default since it round-trips the broadest range of Python
+/// values; `cloudpickle` additionally handles closures and lambdas that plain
+/// `pickle` can't serialize; `json`/`msgpack` trade generality for a portable,
+/// language-agnostic wire format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskCodec {
+    Pickle,
+    CloudPickle,
+    Json,
+    MsgPack,
+}
+
+impl TaskCodec {
+    pub fn from_name(name: Option<&str>) -> PyResult<Self> {
+        match name {
+            None | Some("pickle") => Ok(TaskCodec::Pickle),
+            Some("cloudpickle") => Ok(TaskCodec::CloudPickle),
+            Some("json") => Ok(TaskCodec::Json),
+            Some("msgpack") => Ok(TaskCodec::MsgPack),
+            Some(other) => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown codec '{}', expected 'pickle', 'cloudpickle', 'json' or 'msgpack'",
+                other
+            ))),
+        }
+    }
+
+    fn module_name(self) -> &'static str {
+        match self {
+            TaskCodec::Pickle => "pickle",
+            TaskCodec::CloudPickle => "cloudpickle",
+            TaskCodec::Json => "json",
+            TaskCodec::MsgPack => "msgpack",
+        }
+    }
+
+    fn import<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        py.import(self.module_name()).map_err(|e| {
+            pyo3::exceptions::PyImportError::new_err(format!(
+                "codec '{}' requires the '{}' package to be installed: {}",
+                self.module_name(),
+                self.module_name(),
+                e
+            ))
+        })
+    }
+
+    /// Serialize a Python value to bytes. Raises a clear error when the value isn't
+    /// serializable by the chosen codec instead of silently producing a bad payload.
+    pub fn encode(self, py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<Vec<u8>> {
+        let module = self.import(py)?;
+        match self {
+            TaskCodec::Json => {
+                let text: String = module
+                    .call_method1("dumps", (value,))
+                    .map_err(|e| {
+                        pyo3::exceptions::PyValueError::new_err(format!(
+                            "value is not JSON-serializable: {}",
+                            e
+                        ))
+                    })?
+                    .extract()?;
+                Ok(text.into_bytes())
+            }
+            TaskCodec::Pickle | TaskCodec::CloudPickle | TaskCodec::MsgPack => {
+                let dumped = module.call_method1("dumps", (value,)).map_err(|e| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "value is not serializable with '{}': {}",
+                        self.module_name(),
+                        e
+                    ))
+                })?;
+                let bytes = dumped.downcast::<PyBytes>().map_err(|_| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "'{}'.dumps() did not return bytes",
+                        self.module_name()
+                    ))
+                })?;
+                Ok(bytes.as_bytes().to_vec())
+            }
+        }
+    }
+
+    /// Decode bytes produced by `encode` back into a Python value.
+    pub fn decode(self, py: Python<'_>, data: &[u8]) -> PyResult<PyObject> {
+        let module = self.import(py)?;
+        match self {
+            TaskCodec::Json => {
+                let text = std::str::from_utf8(data).map_err(|e| {
+                    pyo3::exceptions::PyValueError::new_err(format!("JSON payload is not valid UTF-8: {}", e))
+                })?;
+                Ok(module.call_method1("loads", (text,))?.unbind())
+            }
+            TaskCodec::Pickle | TaskCodec::CloudPickle | TaskCodec::MsgPack => {
+                let bytes = PyBytes::new(py, data);
+                Ok(module.call_method1("loads", (bytes,))?.unbind())
+            }
+        }
+    }
+}
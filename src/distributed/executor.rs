@@ -3,20 +3,39 @@ use pyo3::types::{PyAny, PyFunction, PyList, PyTuple};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use uuid::Uuid;
 
 use super::cluster::{ClusterManager, LoadBalancer};
+use super::codec::TaskCodec;
+use super::function_cache::{FunctionCache, FunctionPayload};
+use super::task_store::{task_store_from_spec, TaskStore};
 use crate::error::ParallelExecutionError;
+use crate::fault_tolerance::ChaosConfig;
+
+/// Default number of distinct functions the content-addressed cache keeps pickled
+/// bytes for before evicting the least-recently-used entry.
+const DEFAULT_FUNCTION_CACHE_SIZE: usize = 128;
+/// Default size, in bytes, above which a pickled function skips the cache and is
+/// shipped inline on the task instead (not worth deduplicating a one-off payload).
+const DEFAULT_INLINE_THRESHOLD_BYTES: usize = 64 * 1024;
 
 /// Task to be executed in distributed environment
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DistributedTask {
     pub id: String,
     pub function_name: String,
-    pub args: Vec<String>, // Serialized arguments
+    pub function: FunctionPayload,
+    pub args: Vec<Vec<u8>>, // Arguments serialized with the executor's codec
     pub node_id: Option<String>,
     pub priority: u8,
     pub requirements: HashMap<String, f64>,
+    /// Task IDs that must reach `Completed` before this task is runnable.
+    pub depends_on: Vec<String>,
+    /// Positions in `args` whose real value is a dependency's result rather
+    /// than the placeholder bytes stored there, resolved just before the
+    /// task runs.
+    pub arg_dependencies: Vec<(usize, String)>,
 }
 
 /// Result of a distributed task execution
@@ -24,7 +43,7 @@ pub struct DistributedTask {
 pub struct TaskResult {
     pub task_id: String,
     pub success: bool,
-    pub result: Option<String>, // Serialized result
+    pub result: Option<Vec<u8>>, // Serialized with the executor's codec
     pub error: Option<String>,
     pub execution_time: f64,
     pub node_id: String,
@@ -33,6 +52,8 @@ pub struct TaskResult {
 /// Status of a distributed task
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TaskStatus {
+    /// Waiting on one or more `depends_on` tasks to reach `Completed`.
+    Blocked,
     Pending,
     Assigned,
     Running,
@@ -41,77 +62,346 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+/// Marker passed as a `submit_task` argument in place of a literal value to
+/// say "substitute the result of this upstream task here once it completes".
+/// Using it also implicitly adds the referenced task to `depends_on`.
+#[pyclass]
+#[derive(Clone)]
+pub struct TaskRef {
+    pub task_id: String,
+}
+
+#[pymethods]
+impl TaskRef {
+    #[new]
+    pub fn new(task_id: String) -> Self {
+        Self { task_id }
+    }
+}
+
 /// Distributed executor for running tasks across a cluster
 #[pyclass]
 #[derive(Clone)]
 pub struct DistributedExecutor {
     cluster: Arc<Mutex<ClusterManager>>,
     load_balancer: LoadBalancer,
-    tasks: Arc<Mutex<HashMap<String, (DistributedTask, TaskStatus)>>>,
-    results: Arc<Mutex<HashMap<String, TaskResult>>>,
+    codec: TaskCodec,
+    store: Arc<dyn TaskStore>,
+    function_cache: Arc<FunctionCache>,
+    chaos: Option<ChaosConfig>,
 }
 
 #[pymethods]
 impl DistributedExecutor {
     #[new]
-    pub fn new(cluster_manager: &ClusterManager, load_balancer: Option<&LoadBalancer>) -> Self {
+    #[pyo3(signature = (cluster_manager, load_balancer=None, codec=None, persistence=None, function_cache_size=None, inline_threshold_bytes=None))]
+    pub fn new(
+        py: Python<'_>,
+        cluster_manager: &ClusterManager,
+        load_balancer: Option<&LoadBalancer>,
+        codec: Option<String>,
+        persistence: Option<String>,
+        function_cache_size: Option<usize>,
+        inline_threshold_bytes: Option<usize>,
+    ) -> PyResult<Self> {
         let load_balancer = load_balancer.cloned().unwrap_or_else(|| LoadBalancer::new(None));
-        
-        Self {
+        let codec = TaskCodec::from_name(codec.as_deref())?;
+        let store = task_store_from_spec(persistence.as_deref())?;
+
+        // The function cache is in-memory only and does not survive a restart, so a
+        // recovered `Cached { hash }` payload from a durable store can never resolve.
+        // Force every payload inline (self-contained in the persisted task row)
+        // whenever persistence is anything other than the in-process default.
+        let durable = matches!(persistence.as_deref(), Some(spec) if spec != "memory");
+        let inline_threshold_bytes = if durable {
+            0
+        } else {
+            inline_threshold_bytes.unwrap_or(DEFAULT_INLINE_THRESHOLD_BYTES)
+        };
+        let function_cache = Arc::new(FunctionCache::new(
+            function_cache_size.unwrap_or(DEFAULT_FUNCTION_CACHE_SIZE),
+            inline_threshold_bytes,
+        ));
+
+        let executor = Self {
             cluster: Arc::new(Mutex::new(cluster_manager.clone())),
             load_balancer,
-            tasks: Arc::new(Mutex::new(HashMap::new())),
-            results: Arc::new(Mutex::new(HashMap::new())),
-        }
+            codec,
+            store,
+            function_cache,
+            chaos: None,
+        };
+        // Reopening a durable (`sqlite:`) store against rows left behind by a
+        // crashed process: `Running` tasks were dispatched but never finished, so
+        // re-run them directly, then topologically drain anything left `Blocked`.
+        executor.recover_on_startup(py)?;
+        Ok(executor)
+    }
+
+    /// Attach a chaos-testing config; subsequent `submit_task`/`get_result` calls
+    /// will probabilistically inject latency and failures. Pass `None` to disable.
+    pub fn set_chaos_config(&mut self, chaos: Option<ChaosConfig>) {
+        self.chaos = chaos;
     }
 
     /// Submit a task for distributed execution
+    ///
+    /// There is no real remote dispatch yet (see `cluster_map`'s "call directly" note),
+    /// so the task is run locally right away, but the function and its arguments both
+    /// go through real serialization first (cloudpickle for the function, the
+    /// executor's codec for args and the return value) so they round-trip end to end,
+    /// matching what a genuinely remote worker would have to do. The function is
+    /// hashed and deduplicated through `function_cache` so resubmitting the same
+    /// function doesn't re-ship its pickled closure on every call.
+    ///
+    /// `depends_on` names tasks that must reach `Completed` first; an argument may
+    /// also be a `TaskRef` naming an upstream task whose result should be spliced in
+    /// in its place (implicitly added to `depends_on`). A task with unmet
+    /// dependencies is stored as `Blocked` instead of running immediately — use
+    /// `wait_for_all` to drain it once its parents finish.
+    #[pyo3(signature = (function, args, requirements=None, depends_on=None))]
     pub fn submit_task(
-        &self, 
+        &self,
+        py: Python<'_>,
         function: &Bound<'_, PyFunction>,
         args: &Bound<'_, PyTuple>,
-        requirements: Option<HashMap<String, f64>>
+        requirements: Option<HashMap<String, f64>>,
+        depends_on: Option<Vec<String>>,
     ) -> PyResult<String> {
         let task_id = Uuid::new_v4().to_string();
-        
-        // Serialize function and arguments (simplified)
+
         let function_name = function.getattr("__name__")?.extract::<String>()?;
-        let serialized_args: Vec<String> = args.iter()
-            .map(|arg| format!("{:?}", arg)) // Simplified serialization
-            .collect();
+        let function_payload = self.function_cache.store(py, function)?;
+
+        let mut arg_dependencies = Vec::new();
+        let mut serialized_args = Vec::with_capacity(args.len());
+        for (index, arg) in args.iter().enumerate() {
+            if let Ok(task_ref) = arg.downcast::<TaskRef>() {
+                arg_dependencies.push((index, task_ref.borrow().task_id.clone()));
+                serialized_args.push(Vec::new()); // placeholder, resolved when the task runs
+            } else {
+                serialized_args.push(self.codec.encode(py, &arg)?);
+            }
+        }
+
+        let mut depends_on = depends_on.unwrap_or_default();
+        for (_, dep_id) in &arg_dependencies {
+            if !depends_on.contains(dep_id) {
+                depends_on.push(dep_id.clone());
+            }
+        }
 
         let task = DistributedTask {
             id: task_id.clone(),
             function_name,
+            function: function_payload,
             args: serialized_args,
             node_id: None,
             priority: 5, // Default priority
             requirements: requirements.unwrap_or_default(),
+            depends_on,
+            arg_dependencies,
         };
 
-        // Select a node for the task
-        let cluster = self.cluster.lock().unwrap();
-        let selected_node = self.load_balancer.select_node(&cluster, task.requirements.clone().into())?;
-        drop(cluster);
-
-        let mut tasks = self.tasks.lock().unwrap();
-        tasks.insert(task_id.clone(), (task, TaskStatus::Pending));
+        if self.unmet_dependencies(&task.depends_on)?.is_empty() {
+            self.store.put_task(task.clone(), TaskStatus::Running)?;
+            self.execute_task(py, &task)?;
+        } else {
+            self.store.put_task(task, TaskStatus::Blocked)?;
+        }
 
         Ok(task_id)
     }
 
+    /// Dependency IDs from `depends_on` that have not yet reached `Completed`
+    /// (including ones never submitted, which can never satisfy the wait).
+    fn unmet_dependencies(&self, depends_on: &[String]) -> PyResult<Vec<String>> {
+        let mut unmet = Vec::new();
+        for dep_id in depends_on {
+            let completed = self
+                .store
+                .get(dep_id)?
+                .map(|row| matches!(row.status, TaskStatus::Completed))
+                .unwrap_or(false);
+            if !completed {
+                unmet.push(dep_id.clone());
+            }
+        }
+        Ok(unmet)
+    }
+
+    /// Rebuild the call arguments for `task`, decoding literal args through the
+    /// codec and splicing in each dependency's decoded result at its recorded
+    /// position. Assumes `unmet_dependencies` is already empty for this task.
+    fn resolve_call_args<'py>(
+        &self,
+        py: Python<'py>,
+        task: &DistributedTask,
+    ) -> PyResult<Bound<'py, PyTuple>> {
+        let dep_by_index: HashMap<usize, &String> =
+            task.arg_dependencies.iter().map(|(i, id)| (*i, id)).collect();
+
+        let mut values = Vec::with_capacity(task.args.len());
+        for (index, encoded) in task.args.iter().enumerate() {
+            if let Some(dep_id) = dep_by_index.get(&index) {
+                let dep_result = self
+                    .store
+                    .get(dep_id)?
+                    .and_then(|row| row.result)
+                    .filter(|result| result.success)
+                    .ok_or_else(|| {
+                        ParallelExecutionError::new_err(format!(
+                            "dependency '{}' has no completed result",
+                            dep_id
+                        ))
+                    })?;
+                let bytes = dep_result.result.ok_or_else(|| {
+                    ParallelExecutionError::new_err(format!(
+                        "dependency '{}' produced no result value",
+                        dep_id
+                    ))
+                })?;
+                values.push(self.codec.decode(py, &bytes)?);
+            } else {
+                values.push(self.codec.decode(py, encoded)?);
+            }
+        }
+
+        PyTuple::new(py, values)
+    }
+
+    /// Run a task whose dependencies (if any) are all `Completed`, preferring the
+    /// node that holds a parent's result to reduce data movement, and persist its
+    /// outcome.
+    fn execute_task(&self, py: Python<'_>, task: &DistributedTask) -> PyResult<()> {
+        let preferred_node = task
+            .depends_on
+            .iter()
+            .filter_map(|dep_id| self.store.get(dep_id).ok().flatten())
+            .filter_map(|row| row.result.map(|result| result.node_id))
+            .next();
+
+        let selected_node = match preferred_node {
+            Some(node) => Some(node),
+            None => {
+                let cluster = self.cluster.lock().unwrap();
+                let node = self
+                    .load_balancer
+                    .select_node(&cluster, task.requirements.clone().into())?;
+                drop(cluster);
+                node
+            }
+        };
+
+        // Reconstruct the callable from its pickled form, exactly as a remote worker
+        // that only received `task.function` (not the original Python object) would.
+        let rehydrated = self.function_cache.load(py, &task.function)?;
+        let rehydrated = rehydrated.bind(py);
+        let call_args = self.resolve_call_args(py, task)?;
+
+        let started = Instant::now();
+        let outcome = match &self.chaos {
+            Some(chaos) => chaos.inject().and_then(|()| rehydrated.call1(&call_args)),
+            None => rehydrated.call1(&call_args),
+        };
+        let execution_time = started.elapsed().as_secs_f64();
+        let node_id = selected_node.unwrap_or_else(|| "local".to_string());
+
+        let task_result = match outcome {
+            Ok(value) => match self.codec.encode(py, &value) {
+                Ok(bytes) => TaskResult {
+                    task_id: task.id.clone(),
+                    success: true,
+                    result: Some(bytes),
+                    error: None,
+                    execution_time,
+                    node_id,
+                },
+                Err(e) => TaskResult {
+                    task_id: task.id.clone(),
+                    success: false,
+                    result: None,
+                    error: Some(e.to_string()),
+                    execution_time,
+                    node_id,
+                },
+            },
+            Err(e) => TaskResult {
+                task_id: task.id.clone(),
+                success: false,
+                result: None,
+                error: Some(e.to_string()),
+                execution_time,
+                node_id,
+            },
+        };
+
+        let final_status = if task_result.success { TaskStatus::Completed } else { TaskStatus::Failed };
+        self.store.put_result(task_result, final_status)?;
+
+        Ok(())
+    }
+
+    /// Re-dispatch the crash-interrupted half of `recoverable()`: tasks left
+    /// `Running` by a process that died mid-`execute_task` are re-run from
+    /// scratch (at-least-once, not exactly-once), then any `Blocked` tasks are
+    /// drained topologically. `Pending`/`Assigned` rows are left for the caller
+    /// to pick up via `recoverable_tasks`, since nothing in this process queued
+    /// them.
+    fn recover_on_startup(&self, py: Python<'_>) -> PyResult<()> {
+        for row in self.store.recoverable()? {
+            if matches!(row.status, TaskStatus::Running) {
+                self.execute_task(py, &row.task)?;
+            }
+        }
+        self.drain_blocked_tasks(py)
+    }
+
+    /// Topologically drain `Blocked` tasks: repeatedly run any whose dependencies
+    /// have all completed, until a full pass makes no further progress (the
+    /// remainder are stuck behind a failed/cancelled dependency or a cycle).
+    fn drain_blocked_tasks(&self, py: Python<'_>) -> PyResult<()> {
+        loop {
+            let blocked: Vec<DistributedTask> = self
+                .store
+                .all()?
+                .into_iter()
+                .filter(|row| matches!(row.status, TaskStatus::Blocked))
+                .map(|row| row.task)
+                .collect();
+
+            if blocked.is_empty() {
+                return Ok(());
+            }
+
+            let mut progressed = false;
+            for task in blocked {
+                if self.unmet_dependencies(&task.depends_on)?.is_empty() {
+                    self.store.update_status(&task.id, TaskStatus::Running)?;
+                    self.execute_task(py, &task)?;
+                    progressed = true;
+                }
+            }
+
+            if !progressed {
+                return Ok(());
+            }
+        }
+    }
+
     /// Submit multiple tasks in batch
     pub fn submit_batch(
         &self,
+        py: Python<'_>,
         function: &Bound<'_, PyFunction>,
         args_list: &Bound<'_, PyList>,
         requirements: Option<HashMap<String, f64>>
     ) -> PyResult<Vec<String>> {
         let mut task_ids = Vec::new();
-        
+
         for args in args_list.iter() {
             let args_tuple = args.downcast::<PyTuple>()?;
-            let task_id = self.submit_task(function, args_tuple, requirements.clone())?;
+            let task_id = self.submit_task(py, function, args_tuple, requirements.clone(), None)?;
             task_ids.push(task_id);
         }
 
@@ -120,11 +410,11 @@ impl DistributedExecutor {
 
     /// Get task status
     pub fn get_task_status(&self, task_id: String) -> PyResult<String> {
-        let tasks = self.tasks.lock().unwrap();
-        if let Some((_, status)) = tasks.get(&task_id) {
-            let status_str = match status {
+        if let Some(row) = self.store.get(&task_id)? {
+            let status_str = match row.status {
+                TaskStatus::Blocked => "blocked",
                 TaskStatus::Pending => "pending",
-                TaskStatus::Assigned => "assigned", 
+                TaskStatus::Assigned => "assigned",
                 TaskStatus::Running => "running",
                 TaskStatus::Completed => "completed",
                 TaskStatus::Failed => "failed",
@@ -136,32 +426,42 @@ impl DistributedExecutor {
         }
     }
 
-    /// Get task result (blocking)
-    pub fn get_result(&self, task_id: String, timeout: Option<f64>) -> PyResult<Option<String>> {
+    /// Get task result (blocking), decoded back into a Python object via the
+    /// executor's codec
+    pub fn get_result(&self, py: Python<'_>, task_id: String, timeout: Option<f64>) -> PyResult<Option<PyObject>> {
         // TODO: Implement timeout and actual distributed execution
-        let results = self.results.lock().unwrap();
-        if let Some(result) = results.get(&task_id) {
-            if result.success {
-                Ok(result.result.clone())
-            } else {
-                Err(ParallelExecutionError::new_err(
-                    result.error.clone().unwrap_or_else(|| "Unknown error".to_string())
-                ))
-            }
-        } else {
-            Ok(None)
+        if let Some(chaos) = &self.chaos {
+            chaos.inject()?;
+        }
+
+        match self.store.get(&task_id)?.and_then(|row| row.result) {
+            Some(result) if result.success => match &result.result {
+                Some(bytes) => Ok(Some(self.codec.decode(py, bytes)?)),
+                None => Ok(None),
+            },
+            Some(result) => Err(ParallelExecutionError::new_err(
+                result.error.unwrap_or_else(|| "Unknown error".to_string())
+            )),
+            None => Ok(None),
         }
     }
 
-    /// Wait for all submitted tasks to complete
-    pub fn wait_for_all(&self, timeout: Option<f64>) -> PyResult<HashMap<String, String>> {
+    /// Wait for all submitted tasks to complete, decoding each result via the
+    /// executor's codec. Performs a topological drain first so any `Blocked`
+    /// fan-in/reduce tasks whose dependencies have since finished get to run.
+    pub fn wait_for_all(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<HashMap<String, PyObject>> {
         // TODO: Implement actual waiting logic with timeout
-        let results = self.results.lock().unwrap();
+        self.drain_blocked_tasks(py)?;
+
         let mut all_results = HashMap::new();
-        
-        for (task_id, result) in results.iter() {
-            if result.success {
-                all_results.insert(task_id.clone(), result.result.clone().unwrap_or_default());
+
+        for row in self.store.all()? {
+            if let Some(result) = row.result {
+                if result.success {
+                    if let Some(bytes) = &result.result {
+                        all_results.insert(result.task_id.clone(), self.codec.decode(py, bytes)?);
+                    }
+                }
             }
         }
 
@@ -170,37 +470,52 @@ impl DistributedExecutor {
 
     /// Cancel a task
     pub fn cancel_task(&self, task_id: String) -> PyResult<bool> {
-        let mut tasks = self.tasks.lock().unwrap();
-        if let Some((_, status)) = tasks.get_mut(&task_id) {
-            match status {
-                TaskStatus::Pending | TaskStatus::Assigned => {
-                    *status = TaskStatus::Cancelled;
+        match self.store.get(&task_id)? {
+            Some(row) => match row.status {
+                TaskStatus::Blocked | TaskStatus::Pending | TaskStatus::Assigned => {
+                    self.store.update_status(&task_id, TaskStatus::Cancelled)?;
                     Ok(true)
                 }
-                _ => Ok(false) // Cannot cancel running/completed tasks
-            }
-        } else {
-            Ok(false)
+                _ => Ok(false), // Cannot cancel running/completed tasks
+            },
+            None => Ok(false),
         }
     }
 
+    /// Task IDs left `Pending`/`Assigned`/`Running`/`Blocked` by a crashed executor
+    /// (or one that never got to dispatch them), available for at-least-once
+    /// re-dispatch by the caller. `Running`/`Blocked` rows are already re-dispatched
+    /// automatically by `recover_on_startup` when this store is reopened; this is
+    /// for `Pending`/`Assigned` rows, which nothing in this process queued.
+    pub fn recoverable_tasks(&self) -> PyResult<Vec<String>> {
+        Ok(self
+            .store
+            .recoverable()?
+            .into_iter()
+            .map(|row| row.task.id)
+            .collect())
+    }
+
     /// Get execution statistics
     pub fn get_stats(&self) -> PyResult<HashMap<String, f64>> {
-        let tasks = self.tasks.lock().unwrap();
-        let results = self.results.lock().unwrap();
-        
-        let total_tasks = tasks.len() as f64;
-        let completed_tasks = tasks.values()
-            .filter(|(_, status)| matches!(status, TaskStatus::Completed))
+        let rows = self.store.all()?;
+
+        let total_tasks = rows.len() as f64;
+        let completed_tasks = rows
+            .iter()
+            .filter(|row| matches!(row.status, TaskStatus::Completed))
             .count() as f64;
-        let failed_tasks = tasks.values()
-            .filter(|(_, status)| matches!(status, TaskStatus::Failed))
+        let failed_tasks = rows
+            .iter()
+            .filter(|row| matches!(row.status, TaskStatus::Failed))
             .count() as f64;
-        
-        let avg_execution_time = if !results.is_empty() {
-            results.values()
-                .map(|r| r.execution_time)
-                .sum::<f64>() / results.len() as f64
+
+        let execution_times: Vec<f64> = rows
+            .iter()
+            .filter_map(|row| row.result.as_ref().map(|r| r.execution_time))
+            .collect();
+        let avg_execution_time = if !execution_times.is_empty() {
+            execution_times.iter().sum::<f64>() / execution_times.len() as f64
         } else {
             0.0
         };
@@ -209,7 +524,7 @@ impl DistributedExecutor {
         stats.insert("total_tasks".to_string(), total_tasks);
         stats.insert("completed_tasks".to_string(), completed_tasks);
         stats.insert("failed_tasks".to_string(), failed_tasks);
-        stats.insert("success_rate".to_string(), 
+        stats.insert("success_rate".to_string(),
                     if total_tasks > 0.0 { completed_tasks / total_tasks } else { 0.0 });
         stats.insert("average_execution_time".to_string(), avg_execution_time);
 
@@ -226,7 +541,7 @@ pub fn cluster_map(
     cluster_manager: &ClusterManager,
     chunk_size: Option<usize>
 ) -> PyResult<Vec<PyObject>> {
-    let executor = DistributedExecutor::new(cluster_manager, None);
+    let executor = DistributedExecutor::new(py, cluster_manager, None, None, None, None, None)?;
     
     // Convert iterable to Vec
     let items: Vec<Bound<PyAny>> = iterable.try_iter()?.collect::<Result<Vec<_>, _>>()?;
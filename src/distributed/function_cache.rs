@@ -0,0 +1,134 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyFunction};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// How a task's function payload is carried on `DistributedTask`: a hash
+/// referencing an already-cached cloudpickle blob, or the bytes inline when the
+/// payload is too large to be worth deduplicating.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FunctionPayload {
+    Cached { hash: String },
+    Inline { bytes: Vec<u8> },
+}
+
+struct FunctionCacheInner {
+    bytes_by_hash: HashMap<String, Vec<u8>>,
+    /// Least-recently-used order, oldest first.
+    order: VecDeque<String>,
+}
+
+/// Content-addressed cache for pickled function bytes, so resubmitting the same
+/// function doesn't re-ship its closure on every task. Entries are keyed by the
+/// SHA-256 of the cloudpickle payload and bounded by entry count with LRU eviction;
+/// payloads above `inline_threshold` bytes skip the cache and ride along inline.
+///
+/// This cache is in-memory only and does not survive a process restart, so it must
+/// never be the sole home of a payload a durable `TaskStore` might need to recover
+/// after a crash; `DistributedExecutor::new` sets `inline_threshold` to 0 whenever a
+/// durable store is configured, forcing every payload inline instead.
+pub struct FunctionCache {
+    capacity: usize,
+    inline_threshold: usize,
+    inner: Mutex<FunctionCacheInner>,
+}
+
+impl FunctionCache {
+    pub fn new(capacity: usize, inline_threshold: usize) -> Self {
+        Self {
+            // A capacity of 0 would make `store`'s eviction loop spin forever trying
+            // to make room for an entry that can never fit.
+            capacity: capacity.max(1),
+            inline_threshold,
+            inner: Mutex::new(FunctionCacheInner {
+                bytes_by_hash: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+        }
+    }
+
+    fn cloudpickle(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        py.import("cloudpickle").map_err(|e| {
+            pyo3::exceptions::PyImportError::new_err(format!(
+                "function serialization requires the 'cloudpickle' package: {}",
+                e
+            ))
+        })
+    }
+
+    /// Pickle `function` with cloudpickle and either register it in the LRU cache
+    /// (returning a hash reference) or, if it's larger than `inline_threshold`,
+    /// return the bytes inline without touching the cache.
+    pub fn store(
+        &self,
+        py: Python<'_>,
+        function: &Bound<'_, PyFunction>,
+    ) -> PyResult<FunctionPayload> {
+        let cloudpickle = Self::cloudpickle(py)?;
+        let dumped = cloudpickle
+            .call_method1("dumps", (function,))
+            .map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "function is not serializable with cloudpickle: {}",
+                    e
+                ))
+            })?;
+        let bytes = dumped
+            .downcast::<PyBytes>()
+            .map_err(|_| {
+                pyo3::exceptions::PyValueError::new_err("cloudpickle.dumps() did not return bytes")
+            })?
+            .as_bytes()
+            .to_vec();
+
+        if bytes.len() > self.inline_threshold {
+            return Ok(FunctionPayload::Inline { bytes });
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes);
+        let hash = format!("{:x}", hasher.finalize());
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.bytes_by_hash.contains_key(&hash) {
+            // Touch: move to the back so it's the most-recently-used entry.
+            inner.order.retain(|existing| existing != &hash);
+        } else {
+            inner.bytes_by_hash.insert(hash.clone(), bytes);
+            while inner.order.len() >= self.capacity {
+                if let Some(oldest) = inner.order.pop_front() {
+                    inner.bytes_by_hash.remove(&oldest);
+                }
+            }
+        }
+        inner.order.push_back(hash.clone());
+
+        Ok(FunctionPayload::Cached { hash })
+    }
+
+    /// Resolve a `FunctionPayload` back to a callable, decoding via cloudpickle.
+    pub fn load(&self, py: Python<'_>, payload: &FunctionPayload) -> PyResult<PyObject> {
+        let bytes = match payload {
+            FunctionPayload::Inline { bytes } => bytes.clone(),
+            FunctionPayload::Cached { hash } => self
+                .inner
+                .lock()
+                .unwrap()
+                .bytes_by_hash
+                .get(hash)
+                .cloned()
+                .ok_or_else(|| {
+                    pyo3::exceptions::PyKeyError::new_err(format!(
+                        "function hash '{}' not found in cache (evicted or never submitted)",
+                        hash
+                    ))
+                })?,
+        };
+
+        let cloudpickle = Self::cloudpickle(py)?;
+        let py_bytes = PyBytes::new(py, &bytes);
+        Ok(cloudpickle.call_method1("loads", (py_bytes,))?.unbind())
+    }
+}
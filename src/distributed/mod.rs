@@ -0,0 +1,15 @@
+pub mod cluster;
+pub mod codec;
+pub mod executor;
+pub mod function_cache;
+pub mod operations;
+pub mod task_store;
+pub mod work_stealing;
+
+pub use cluster::*;
+pub use codec::*;
+pub use executor::*;
+pub use function_cache::*;
+pub use operations::*;
+pub use task_store::*;
+pub use work_stealing::*;
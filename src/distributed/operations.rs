@@ -1,10 +1,32 @@
+use pyo3::basic::CompareOp;
 use pyo3::prelude::*;
 use pyo3::types::{PyAny, PyFunction, PyList, PyTuple};
+use rayon::prelude::*;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use super::cluster::ClusterManager;
+use super::codec::TaskCodec;
 use super::executor::{DistributedExecutor, cluster_map, distributed_reduce};
 
+/// Real 3-way comparison between two Python objects, driven through the rich-comparison
+/// protocol (`lt`/`gt`) rather than calling `__lt__`/`__gt__` directly, so `NotImplemented`
+/// from mismatched operands and reflected-operator fallback are handled the way `sorted()`
+/// handles them. Any exception raised propagates instead of being swallowed into a default
+/// ordering.
+fn python_cmp(py: Python<'_>, a: &PyObject, b: &PyObject) -> PyResult<Ordering> {
+    let a = a.bind(py);
+    let b = b.bind(py);
+
+    if a.rich_compare(b, CompareOp::Lt)?.is_truthy()? {
+        return Ok(Ordering::Less);
+    }
+    if a.rich_compare(b, CompareOp::Gt)?.is_truthy()? {
+        return Ok(Ordering::Greater);
+    }
+    Ok(Ordering::Equal)
+}
+
 /// High-level distributed operations
 pub struct DistributedOps;
 
@@ -34,9 +56,9 @@ impl DistributedOps {
         cluster: &ClusterManager,
         chunk_size: Option<usize>
     ) -> PyResult<Vec<PyObject>> {
-        let executor = DistributedExecutor::new(&cluster, None);
+        let executor = DistributedExecutor::new(py, &cluster, None, None, None, None, None)?;
         let items: Vec<Bound<PyAny>> = iterable.try_iter()?.collect::<Result<Vec<_>, _>>()?;
-        
+
         let chunk_size = chunk_size.unwrap_or(std::cmp::max(1, items.len() / 10));
         let mut filtered_results = Vec::new();
         
@@ -56,50 +78,64 @@ impl DistributedOps {
     }
 
     /// Distributed parallel sort with custom key function
+    ///
+    /// Implemented as a Schwartzian transform (decorate-sort-undecorate) so `key_fn`
+    /// is evaluated exactly once per element instead of once per comparison: a first
+    /// pass builds `(key, item)` pairs, then the pairs are sorted in place
+    /// with `par_sort_unstable_by` using a real 3-way Python comparator (rich-compare
+    /// `lt`, falling back to `gt`, with neither true meaning equal), and comparison
+    /// errors — including `TypeError` for unorderable operands — propagate instead
+    /// of silently defaulting to `Greater`. The key pass is not parallel: it runs
+    /// sequentially on the calling thread.
     pub fn parallel_sort(
         py: Python<'_>,
         iterable: Bound<'_, PyAny>,
         key_fn: Option<Bound<'_, PyFunction>>,
-        cluster: &ClusterManager
+        _cluster: &ClusterManager
     ) -> PyResult<Vec<PyObject>> {
-        let executor = DistributedExecutor::new(&cluster, None);
         let items: Vec<Bound<PyAny>> = iterable.try_iter()?.collect::<Result<Vec<_>, _>>()?;
-        
-        // Convert to owned objects for sorting
-        let mut items: Vec<PyObject> = items.into_iter().map(|item| item.unbind()).collect();
-        
-        if let Some(key_fn) = key_fn {
-            // Sort with key function
-            items.sort_by(|a, b| {
-                // This is a simplified comparison - real distributed sort would be more complex
-                let args_a = PyTuple::new(py, &[a.bind(py)]).unwrap();
-                let args_b = PyTuple::new(py, &[b.bind(py)]).unwrap();
-                let key_a = key_fn.call1(&args_a).unwrap();
-                let key_b = key_fn.call1(&args_b).unwrap();
-                
-                // Simple string comparison for now
-                let cmp = key_a.call_method1("__lt__", (&key_b,))
-                    .unwrap_or_else(|_| py.eval(c"False", None, None).unwrap());
-                if cmp.is_truthy().unwrap_or(false) {
-                    std::cmp::Ordering::Less
-                } else {
-                    std::cmp::Ordering::Greater
-                }
-            });
-        } else {
-            // Sort without key function
-            items.sort_by(|a, b| {
-                let cmp = a.bind(py).call_method1("__lt__", (b.bind(py),))
-                    .unwrap_or_else(|_| py.eval(c"False", None, None).unwrap());
-                if cmp.is_truthy().unwrap_or(false) {
-                    std::cmp::Ordering::Less
-                } else {
-                    std::cmp::Ordering::Greater
+
+        let key_fn: Option<PyObject> = key_fn.map(|f| f.unbind());
+
+        // Decorate: compute each item's key exactly once
+        let mut decorated: Vec<(PyObject, PyObject)> = items
+            .into_iter()
+            .map(|item| -> PyResult<(PyObject, PyObject)> {
+                let item = item.unbind();
+                let key = match &key_fn {
+                    Some(key_fn) => {
+                        let args = PyTuple::new(py, &[item.bind(py)])?;
+                        key_fn.bind(py).call1(&args)?.unbind()
+                    }
+                    None => item.clone_ref(py),
+                };
+                Ok((key, item))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        // Sort: par_sort_unstable_by still runs across rayon's thread pool, but each
+        // comparison re-acquires the GIL, so comparisons themselves serialize rather
+        // than scale with it.
+        // Errors from the comparator are still surfaced rather than swallowed.
+        let sort_error = std::sync::Mutex::new(None);
+        decorated.par_sort_unstable_by(|(key_a, _), (key_b, _)| {
+            if sort_error.lock().unwrap().is_some() {
+                return Ordering::Equal;
+            }
+            match Python::with_gil(|py| python_cmp(py, key_a, key_b)) {
+                Ok(ordering) => ordering,
+                Err(e) => {
+                    *sort_error.lock().unwrap() = Some(e);
+                    Ordering::Equal
                 }
-            });
+            }
+        });
+        if let Some(e) = sort_error.into_inner().unwrap() {
+            return Err(e);
         }
-        
-        Ok(items)
+
+        // Undecorate: drop the keys, keep the items in sorted order
+        Ok(decorated.into_iter().map(|(_, item)| item).collect())
     }
 
     /// Distributed group by operation
@@ -109,9 +145,9 @@ impl DistributedOps {
         key_function: Bound<'_, PyFunction>,
         cluster: &ClusterManager
     ) -> PyResult<HashMap<String, Vec<PyObject>>> {
-        let executor = DistributedExecutor::new(&cluster, None);
+        let executor = DistributedExecutor::new(py, &cluster, None, None, None, None, None)?;
         let items: Vec<Bound<PyAny>> = iterable.try_iter()?.collect::<Result<Vec<_>, _>>()?;
-        
+
         let mut groups: HashMap<String, Vec<PyObject>> = HashMap::new();
         
         for item in items {
@@ -148,19 +184,27 @@ pub struct DistributedBatchProcessor {
 
 #[pymethods]
 impl DistributedBatchProcessor {
+    /// `codec` selects how task arguments and results are serialized between
+    /// submission and retrieval: `"pickle"` (default), `"cloudpickle"` (closures and
+    /// lambdas), `"json"`, or `"msgpack"`.
     #[new]
-    pub fn new(cluster: ClusterManager, batch_size: Option<usize>) -> Self {
+    #[pyo3(signature = (cluster, batch_size=None, codec=None))]
+    pub fn new(py: Python<'_>, cluster: ClusterManager, batch_size: Option<usize>, codec: Option<String>) -> PyResult<Self> {
         let batch_size = batch_size.unwrap_or(100);
-        let executor = DistributedExecutor::new(&cluster, None);
-        
-        Self {
+        // Validate eagerly so a bad codec name fails at construction, not on first batch
+        TaskCodec::from_name(codec.as_deref())?;
+        let executor = DistributedExecutor::new(py, &cluster, None, codec, None, None, None)?;
+
+        Ok(Self {
             cluster,
             batch_size,
             executor,
-        }
+        })
     }
 
-    /// Process data in batches with optional progress callback
+    /// Process data in batches with optional progress callback. Results are real
+    /// Python objects round-tripped through the processor's codec, not `eval`'d
+    /// reprs, so closures, numpy arrays and dataclasses all survive the trip.
     pub fn process_batches(
         &self,
         py: Python<'_>,
@@ -175,24 +219,23 @@ impl DistributedBatchProcessor {
         // Process in batches
         for chunk in data.iter().collect::<Vec<_>>().chunks(self.batch_size) {
             let chunk_list = PyList::new(py, chunk.iter().cloned())?;
-            
+
             let task_ids = self.executor.submit_batch(
-                &function, 
-                &chunk_list, 
+                py,
+                &function,
+                &chunk_list,
                 None
             )?;
-            
+
             // Wait for results and collect them
             for task_id in task_ids {
-                if let Some(result_str) = self.executor.get_result(task_id, Some(30.0))? {
-                    // In a real implementation, this would deserialize the result properly
-                    let result_cstr = std::ffi::CString::new(result_str).unwrap();
-                    all_results.push(py.eval(result_cstr.as_c_str(), None, None)?.unbind());
+                if let Some(result) = self.executor.get_result(py, task_id, Some(30.0))? {
+                    all_results.push(result);
                 }
             }
-            
+
             processed += chunk.len();
-            
+
             // Call progress callback if provided
             if let Some(callback) = &progress_callback {
                 let progress = processed as f64 / total_items as f64;
@@ -0,0 +1,358 @@
+use pyo3::prelude::*;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::executor::{DistributedTask, TaskResult, TaskStatus};
+
+/// A persisted task row: the task itself, its current status, and the result once
+/// the task has finished.
+#[derive(Debug, Clone)]
+pub struct StoredTask {
+    pub task: DistributedTask,
+    pub status: TaskStatus,
+    pub result: Option<TaskResult>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Persistence backend for `DistributedExecutor`'s task queue. Implementations are
+/// `Send + Sync` so multiple executors can share one. A durable implementation
+/// (`SqliteTaskStore`) survives a process restart, so a crashed worker's incomplete
+/// tasks can be re-dispatched at least once by whoever picks the store back up;
+/// `InMemoryTaskStore` keeps nothing across a restart.
+pub trait TaskStore: Send + Sync {
+    fn put_task(&self, task: DistributedTask, status: TaskStatus) -> PyResult<()>;
+    fn update_status(&self, task_id: &str, status: TaskStatus) -> PyResult<()>;
+    fn put_result(&self, result: TaskResult, status: TaskStatus) -> PyResult<()>;
+    fn get(&self, task_id: &str) -> PyResult<Option<StoredTask>>;
+    fn all(&self) -> PyResult<Vec<StoredTask>>;
+    /// Tasks left `Pending`/`Assigned`/`Running`/`Blocked` by a crashed executor
+    /// (or one that never got to dispatch them), for at-least-once re-dispatch by
+    /// a fresh executor pointed at the same store. `submit_task` only ever persists
+    /// `Running` or `Blocked`, so in practice those are the two statuses a crash
+    /// actually leaves behind; `Pending`/`Assigned` are included for dispatchers
+    /// that queue a task before it runs.
+    fn recoverable(&self) -> PyResult<Vec<StoredTask>>;
+}
+
+fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as u64
+}
+
+/// Default in-process store: matches `DistributedExecutor`'s pre-persistence
+/// behavior and backs `DistributedExecutor::new(..., persistence=None)`. Nothing
+/// survives a restart, but it needs no external dependency.
+#[derive(Default)]
+pub struct InMemoryTaskStore {
+    rows: Mutex<HashMap<String, StoredTask>>,
+}
+
+impl TaskStore for InMemoryTaskStore {
+    fn put_task(&self, task: DistributedTask, status: TaskStatus) -> PyResult<()> {
+        let now = now_micros();
+        self.rows.lock().unwrap().insert(
+            task.id.clone(),
+            StoredTask {
+                task,
+                status,
+                result: None,
+                created_at: now,
+                updated_at: now,
+            },
+        );
+        Ok(())
+    }
+
+    fn update_status(&self, task_id: &str, status: TaskStatus) -> PyResult<()> {
+        if let Some(row) = self.rows.lock().unwrap().get_mut(task_id) {
+            row.status = status;
+            row.updated_at = now_micros();
+        }
+        Ok(())
+    }
+
+    fn put_result(&self, result: TaskResult, status: TaskStatus) -> PyResult<()> {
+        let mut rows = self.rows.lock().unwrap();
+        if let Some(row) = rows.get_mut(&result.task_id) {
+            row.status = status;
+            row.result = Some(result);
+            row.updated_at = now_micros();
+        }
+        Ok(())
+    }
+
+    fn get(&self, task_id: &str) -> PyResult<Option<StoredTask>> {
+        Ok(self.rows.lock().unwrap().get(task_id).cloned())
+    }
+
+    fn all(&self) -> PyResult<Vec<StoredTask>> {
+        Ok(self.rows.lock().unwrap().values().cloned().collect())
+    }
+
+    fn recoverable(&self) -> PyResult<Vec<StoredTask>> {
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|row| matches!(
+                row.status,
+                TaskStatus::Pending | TaskStatus::Assigned | TaskStatus::Running | TaskStatus::Blocked
+            ))
+            .cloned()
+            .collect())
+    }
+}
+
+/// SQLite-backed store. One `tasks` table holds the serialized `DistributedTask`,
+/// its status, and the serialized `TaskResult` once available, so a crashed process
+/// (or a fresh `DistributedExecutor` opened against the same file) can pick its
+/// unfinished tasks back up; a task that was running at the crash is re-run from
+/// the start.
+pub struct SqliteTaskStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteTaskStore {
+    pub fn open(path: &str) -> PyResult<Self> {
+        let conn = Connection::open(path).map_err(|e| {
+            pyo3::exceptions::PyIOError::new_err(format!(
+                "Failed to open task store at '{}': {}",
+                path, e
+            ))
+        })?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                id TEXT PRIMARY KEY,
+                task_json TEXT NOT NULL,
+                status TEXT NOT NULL,
+                priority INTEGER NOT NULL,
+                node_id TEXT,
+                result_json TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+        )
+        .map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "Failed to initialize task store schema: {}",
+                e
+            ))
+        })?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn status_str(status: &TaskStatus) -> &'static str {
+        match status {
+            TaskStatus::Blocked => "blocked",
+            TaskStatus::Pending => "pending",
+            TaskStatus::Assigned => "assigned",
+            TaskStatus::Running => "running",
+            TaskStatus::Completed => "completed",
+            TaskStatus::Failed => "failed",
+            TaskStatus::Cancelled => "cancelled",
+        }
+    }
+
+    fn status_from_str(value: &str) -> TaskStatus {
+        match value {
+            "blocked" => TaskStatus::Blocked,
+            "pending" => TaskStatus::Pending,
+            "assigned" => TaskStatus::Assigned,
+            "running" => TaskStatus::Running,
+            "completed" => TaskStatus::Completed,
+            "failed" => TaskStatus::Failed,
+            _ => TaskStatus::Cancelled,
+        }
+    }
+
+    fn row_to_stored(
+        task_json: String,
+        status: String,
+        result_json: Option<String>,
+        created_at: u64,
+        updated_at: u64,
+    ) -> PyResult<StoredTask> {
+        let task: DistributedTask = serde_json::from_str(&task_json).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Corrupt task row: {}", e))
+        })?;
+        let result = result_json
+            .map(|raw| serde_json::from_str::<TaskResult>(&raw))
+            .transpose()
+            .map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!("Corrupt result row: {}", e))
+            })?;
+
+        Ok(StoredTask {
+            task,
+            status: Self::status_from_str(&status),
+            result,
+            created_at,
+            updated_at,
+        })
+    }
+}
+
+impl TaskStore for SqliteTaskStore {
+    fn put_task(&self, task: DistributedTask, status: TaskStatus) -> PyResult<()> {
+        let now = now_micros();
+        let task_json = serde_json::to_string(&task).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Failed to serialize task: {}", e))
+        })?;
+
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT OR REPLACE INTO tasks
+                    (id, task_json, status, priority, node_id, result_json, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, NULL, ?6, ?6)",
+                params![
+                    task.id,
+                    task_json,
+                    Self::status_str(&status),
+                    task.priority,
+                    task.node_id,
+                    now
+                ],
+            )
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to persist task: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    fn update_status(&self, task_id: &str, status: TaskStatus) -> PyResult<()> {
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE tasks SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                params![Self::status_str(&status), now_micros(), task_id],
+            )
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to update task status: {}",
+                    e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    fn put_result(&self, result: TaskResult, status: TaskStatus) -> PyResult<()> {
+        let result_json = serde_json::to_string(&result).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Failed to serialize result: {}", e))
+        })?;
+
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE tasks SET status = ?1, result_json = ?2, updated_at = ?3 WHERE id = ?4",
+                params![
+                    Self::status_str(&status),
+                    result_json,
+                    now_micros(),
+                    result.task_id
+                ],
+            )
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to persist task result: {}",
+                    e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    fn get(&self, task_id: &str) -> PyResult<Option<StoredTask>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT task_json, status, result_json, created_at, updated_at
+             FROM tasks WHERE id = ?1",
+            params![task_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, u64>(3)?,
+                    row.get::<_, u64>(4)?,
+                ))
+            },
+        );
+
+        match result {
+            Ok((task_json, status, result_json, created_at, updated_at)) => Ok(Some(
+                Self::row_to_stored(task_json, status, result_json, created_at, updated_at)?,
+            )),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(e.to_string())),
+        }
+    }
+
+    fn all(&self) -> PyResult<Vec<StoredTask>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT task_json, status, result_json, created_at, updated_at FROM tasks")
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, u64>(3)?,
+                    row.get::<_, u64>(4)?,
+                ))
+            })
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+        rows.map(|r| {
+            let (task_json, status, result_json, created_at, updated_at) =
+                r.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+            Self::row_to_stored(task_json, status, result_json, created_at, updated_at)
+        })
+        .collect()
+    }
+
+    fn recoverable(&self) -> PyResult<Vec<StoredTask>> {
+        Ok(self
+            .all()?
+            .into_iter()
+            .filter(|row| matches!(
+                row.status,
+                TaskStatus::Pending | TaskStatus::Assigned | TaskStatus::Running | TaskStatus::Blocked
+            ))
+            .collect())
+    }
+}
+
+/// Build a `TaskStore` from an opt-in persistence spec: `None` (or `"memory"`) for
+/// the in-process default, `"sqlite:<path>"` for a durable, crash-recoverable store
+/// that multiple `DistributedExecutor`s can share by pointing at the same file.
+pub fn task_store_from_spec(spec: Option<&str>) -> PyResult<std::sync::Arc<dyn TaskStore>> {
+    match spec {
+        None | Some("memory") => Ok(std::sync::Arc::new(InMemoryTaskStore::default())),
+        Some(rest) => match rest.strip_prefix("sqlite:") {
+            Some(path) => Ok(std::sync::Arc::new(SqliteTaskStore::open(path)?)),
+            None => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown persistence spec '{}', expected 'memory' or 'sqlite:<path>'",
+                rest
+            ))),
+        },
+    }
+}
@@ -0,0 +1,182 @@
+use crossbeam_deque::{Steal, Stealer, Worker};
+use pyo3::prelude::*;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use super::cluster::ClusterManager;
+
+/// A contiguous slice of the input, identified by `[start, end)` indices, handed
+/// to one node at a time
+#[derive(Debug, Clone, Copy)]
+struct TaskChunk {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VictimSelection {
+    Random,
+    RoundRobin,
+}
+
+struct NodeDeque {
+    worker: Worker<TaskChunk>,
+    stealer: Stealer<TaskChunk>,
+    in_flight: AtomicUsize,
+}
+
+/// Cross-node work-stealing scheduler for the distributed layer, modeled on
+/// Chase-Lev deques (the same design crossbeam-deque uses): each node owns a local
+/// LIFO deque of chunks and a `Stealer` handle is shared with every other node.
+/// A node pops its own work first; once its deque is empty it steals from the top
+/// of another node's deque instead of blocking on `get_result` timeouts, so fast
+/// nodes automatically drain chunks originally assigned to a straggler.
+#[pyclass]
+pub struct DistributedWorkStealingScheduler {
+    node_ids: Vec<String>,
+    deques: Mutex<HashMap<String, NodeDeque>>,
+    /// Max victim nodes probed per steal attempt before reporting no work available
+    steal_threshold: usize,
+    victim_selection: VictimSelection,
+    rr_cursor: AtomicUsize,
+}
+
+#[pymethods]
+impl DistributedWorkStealingScheduler {
+    /// Seed one deque per active cluster node, splitting `total_items` proportionally
+    /// to each node's observed-throughput weight (`ClusterManager::get_node_weights`)
+    /// rather than evenly, so the node most likely to finish first isn't given the
+    /// same share as a heavily loaded one.
+    #[new]
+    #[pyo3(signature = (cluster, total_items, steal_threshold=1, victim_selection=None))]
+    pub fn new(
+        cluster: &ClusterManager,
+        total_items: usize,
+        steal_threshold: usize,
+        victim_selection: Option<String>,
+    ) -> PyResult<Self> {
+        let victim_selection = match victim_selection.as_deref() {
+            None | Some("random") => VictimSelection::Random,
+            Some("round_robin") => VictimSelection::RoundRobin,
+            Some(other) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown victim_selection '{}', expected 'random' or 'round_robin'",
+                    other
+                )))
+            }
+        };
+
+        let node_ids = cluster.get_active_nodes()?;
+        if node_ids.is_empty() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "cannot schedule work across a cluster with no active nodes",
+            ));
+        }
+
+        let weights = cluster.get_node_weights()?;
+        let total_weight: f64 = node_ids.iter().map(|id| weights.get(id).copied().unwrap_or(1.0)).sum();
+
+        let mut deques = HashMap::with_capacity(node_ids.len());
+        let mut offset = 0usize;
+        for (i, node_id) in node_ids.iter().enumerate() {
+            let weight = weights.get(node_id).copied().unwrap_or(1.0);
+            let share = if total_weight > 0.0 {
+                ((weight / total_weight) * total_items as f64).round() as usize
+            } else {
+                total_items / node_ids.len()
+            };
+            let is_last = i == node_ids.len() - 1;
+            let end = if is_last { total_items } else { (offset + share).min(total_items) };
+
+            let worker = Worker::new_lifo();
+            let stealer = worker.stealer();
+            if offset < end {
+                worker.push(TaskChunk { start: offset, end });
+            }
+            offset = end;
+
+            deques.insert(
+                node_id.clone(),
+                NodeDeque { worker, stealer, in_flight: AtomicUsize::new(0) },
+            );
+        }
+
+        Ok(Self {
+            node_ids,
+            deques: Mutex::new(deques),
+            steal_threshold: steal_threshold.max(1),
+            victim_selection,
+            rr_cursor: AtomicUsize::new(0),
+        })
+    }
+
+    /// Pop the next `(start, end)` chunk for `node_id`: from its own deque first,
+    /// falling back to stealing from up to `steal_threshold` other nodes' deques
+    /// (chosen per `victim_selection`) once its own deque is empty. Returns `None`
+    /// when its own deque and every probed victim's deque are empty; nodes beyond
+    /// the first `steal_threshold` victims are not probed.
+    pub fn pop_chunk(&self, node_id: String) -> PyResult<Option<(usize, usize)>> {
+        let deques = self.deques.lock().unwrap();
+        let own = deques
+            .get(&node_id)
+            .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(format!("unknown node '{}'", node_id)))?;
+
+        if let Some(chunk) = own.worker.pop() {
+            own.in_flight.fetch_add(1, Ordering::Relaxed);
+            return Ok(Some((chunk.start, chunk.end)));
+        }
+
+        for victim_id in self.select_victims(&node_id).into_iter().take(self.steal_threshold) {
+            let Some(victim) = deques.get(&victim_id) else { continue };
+            loop {
+                match victim.stealer.steal() {
+                    Steal::Success(chunk) => {
+                        own.in_flight.fetch_add(1, Ordering::Relaxed);
+                        return Ok(Some((chunk.start, chunk.end)));
+                    }
+                    Steal::Retry => continue,
+                    Steal::Empty => break,
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Mark one of `node_id`'s in-flight chunks as finished
+    pub fn complete_chunk(&self, node_id: String) -> PyResult<()> {
+        let deques = self.deques.lock().unwrap();
+        if let Some(own) = deques.get(&node_id) {
+            own.in_flight.fetch_sub(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Snapshot of in-flight chunk counts per node, useful for spotting a straggler
+    pub fn in_flight_counts(&self) -> PyResult<HashMap<String, usize>> {
+        let deques = self.deques.lock().unwrap();
+        Ok(deques.iter().map(|(id, d)| (id.clone(), d.in_flight.load(Ordering::Relaxed))).collect())
+    }
+}
+
+impl DistributedWorkStealingScheduler {
+    /// Candidate victim nodes for `node_id`, ordered per `victim_selection`
+    fn select_victims(&self, node_id: &str) -> Vec<String> {
+        let mut candidates: Vec<String> = self.node_ids.iter().filter(|id| id.as_str() != node_id).cloned().collect();
+        if candidates.is_empty() {
+            return candidates;
+        }
+
+        match self.victim_selection {
+            VictimSelection::Random => candidates.shuffle(&mut rand::thread_rng()),
+            VictimSelection::RoundRobin => {
+                let cursor = self.rr_cursor.fetch_add(1, Ordering::Relaxed) % candidates.len();
+                candidates.rotate_left(cursor);
+            }
+        }
+
+        candidates
+    }
+}
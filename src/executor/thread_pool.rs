@@ -1,48 +1,294 @@
+use crate::fault_tolerance::ChaosConfig;
+use pyo3::exceptions::{PyRuntimeError, PyTimeoutError};
 use pyo3::prelude::*;
 use pyo3::types::{PyList, PyTuple};
 use rayon::prelude::*;
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Shared slot a `submit`/`submit_with_args` call is resolved through: `None`
+/// until the worker that ran it (immediately via the pool, or later via a
+/// throttled flush) stores the outcome and wakes anyone waiting in `PyFuture::result`.
+type SubmitSlot = Arc<(Mutex<Option<PyResult<PyObject>>>, Condvar)>;
+
+/// A buffered call awaiting the next throttled flush.
+struct PendingSubmission {
+    func: PyObject,
+    args: Option<Py<PyTuple>>,
+    slot: SubmitSlot,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Handle returned by `submit`/`submit_with_args`. The call runs on the
+/// executor's thread pool (or is buffered for the next throttled flush); this
+/// is the awaitable/poll-able handle to its eventual outcome, in the spirit of
+/// `concurrent.futures.Future` and `asyncio`'s awaitable protocol.
+#[pyclass]
+pub struct PyFuture {
+    slot: SubmitSlot,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl PyFuture {
+    fn new(slot: SubmitSlot, cancelled: Arc<AtomicBool>) -> Self {
+        Self { slot, cancelled }
+    }
+}
+
+#[pymethods]
+impl PyFuture {
+    /// Block (releasing the GIL so the worker can acquire it) until the call
+    /// finishes or `timeout` seconds elapse, then return its result or raise
+    /// its exception. Raises `TimeoutError` if `timeout` elapses first.
+    #[pyo3(signature = (timeout=None))]
+    pub fn result(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<PyObject> {
+        let (lock, cvar) = &*self.slot;
+        py.allow_threads(|| {
+            let mut guard = lock.lock().unwrap();
+            match timeout {
+                Some(secs) => {
+                    let _ = cvar
+                        .wait_timeout_while(guard, Duration::from_secs_f64(secs.max(0.0)), |outcome| {
+                            outcome.is_none()
+                        })
+                        .unwrap();
+                }
+                None => {
+                    while guard.is_none() {
+                        guard = cvar.wait(guard).unwrap();
+                    }
+                }
+            }
+        });
+
+        // Clone rather than take the stored outcome, so `done()` stays true and
+        // `result()` can be called again.
+        let outcome = lock.lock().unwrap();
+        match outcome.as_ref() {
+            Some(Ok(value)) => Ok(value.clone_ref(py)),
+            Some(Err(err)) => Err(err.clone_ref(py)),
+            None => Err(PyTimeoutError::new_err("future did not complete within timeout")),
+        }
+    }
+
+    /// Whether the call has finished (successfully, with an exception, or cancelled).
+    pub fn done(&self) -> bool {
+        let (lock, _) = &*self.slot;
+        lock.lock().unwrap().is_some()
+    }
+
+    /// Best-effort cancellation: only takes effect if the call hasn't started
+    /// running yet (e.g. still sitting in the throttled buffer), in which case
+    /// the call is skipped and resolves with an error. Returns `False` if the
+    /// call has already finished and `True` otherwise; a call that is already
+    /// running is not stopped, and `True` is still returned for it.
+    pub fn cancel(&self) -> bool {
+        if self.done() {
+            return false;
+        }
+        self.cancelled.store(true, Ordering::Relaxed);
+        true
+    }
+
+    pub fn __await__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Drives the simplified awaitable protocol: the call already runs on a
+    /// background thread rather than the event loop, so `__next__` just blocks
+    /// until it finishes and raises `StopIteration(result)` as `await` expects.
+    pub fn __next__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let value = self.result(py, None)?;
+        Err(pyo3::exceptions::PyStopIteration::new_err(value))
+    }
+}
 
 /// Task executor for managing parallel tasks
 #[pyclass]
 pub struct Executor {
     #[pyo3(get, set)]
     pub max_workers: usize,
-    thread_pool: Option<rayon::ThreadPool>,
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+    chaos: Option<ChaosConfig>,
+    /// When set, `submit`/`submit_with_args` buffer instead of being spawned onto
+    /// the pool right away; see
+    /// `PendingSubmission` and the flush thread spawned in `new`.
+    throttle_ms: Option<u64>,
+    pending: Arc<Mutex<VecDeque<PendingSubmission>>>,
+    buffered_count: Arc<AtomicUsize>,
+    dispatched_count: Arc<AtomicUsize>,
+    flush_running: Arc<AtomicBool>,
+    flush_notify: Arc<(Mutex<bool>, Condvar)>,
+    flush_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Executor {
+    /// Run a single buffered or freshly-dispatched call: acquire the GIL, invoke
+    /// the callable (unless cancelled first), then stash the outcome and wake
+    /// whoever is waiting on the `PyFuture`.
+    fn run_submission(func: PyObject, args: Option<Py<PyTuple>>, slot: &SubmitSlot, cancelled: &AtomicBool) {
+        let outcome = if cancelled.load(Ordering::Relaxed) {
+            Err(PyRuntimeError::new_err("submission was cancelled before it started"))
+        } else {
+            Python::with_gil(|py| {
+                let bound_func = func.bind(py);
+                let result = match &args {
+                    Some(a) => bound_func.call1(a.bind(py)),
+                    None => bound_func.call0(),
+                };
+                result.map(|value| value.unbind())
+            })
+        };
+
+        let (lock, cvar) = &**slot;
+        *lock.lock().unwrap() = Some(outcome);
+        cvar.notify_all();
+    }
+
+    /// Run every buffered submission through the thread pool in one rayon scope,
+    /// coalescing whatever accumulated since the last flush into a single batch.
+    fn flush_pending(
+        pending: &Mutex<VecDeque<PendingSubmission>>,
+        buffered_count: &AtomicUsize,
+        dispatched_count: &AtomicUsize,
+        thread_pool: &rayon::ThreadPool,
+    ) {
+        let batch: Vec<PendingSubmission> = {
+            let mut guard = pending.lock().unwrap();
+            guard.drain(..).collect()
+        };
+
+        if batch.is_empty() {
+            return;
+        }
+        let batch_len = batch.len();
+        buffered_count.fetch_sub(batch_len, Ordering::Relaxed);
+
+        thread_pool.install(|| {
+            batch.into_par_iter().for_each(|item| {
+                Self::run_submission(item.func, item.args, &item.slot, &item.cancelled);
+            });
+        });
+
+        dispatched_count.fetch_add(batch_len, Ordering::Relaxed);
+    }
+
+    /// Enqueue `func`/`args` to run on the thread pool and return a `PyFuture`
+    /// immediately: buffered for the next throttled flush if `throttle_ms` is
+    /// set, otherwise spawned onto the pool right away.
+    fn dispatch(&self, py: Python<'_>, func: PyObject, args: Option<Py<PyTuple>>) -> PyResult<Py<PyFuture>> {
+        let slot: SubmitSlot = Arc::new((Mutex::new(None), Condvar::new()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        if self.throttle_ms.is_some() {
+            self.pending.lock().unwrap().push_back(PendingSubmission {
+                func,
+                args,
+                slot: Arc::clone(&slot),
+                cancelled: Arc::clone(&cancelled),
+            });
+            self.buffered_count.fetch_add(1, Ordering::Relaxed);
+        } else if let Some(pool) = &self.thread_pool {
+            let slot_for_worker = Arc::clone(&slot);
+            let cancelled_for_worker = Arc::clone(&cancelled);
+            pool.spawn(move || {
+                Self::run_submission(func, args, &slot_for_worker, &cancelled_for_worker);
+            });
+        } else {
+            // Pool already shut down; run inline so the future still resolves.
+            Self::run_submission(func, args, &slot, &cancelled);
+        }
+
+        Py::new(py, PyFuture::new(slot, cancelled))
+    }
 }
 
 #[pymethods]
 impl Executor {
     #[new]
-    #[pyo3(signature = (max_workers = None))]
-    pub fn new(max_workers: Option<usize>) -> PyResult<Self> {
+    #[pyo3(signature = (max_workers = None, throttle_ms = None))]
+    pub fn new(max_workers: Option<usize>, throttle_ms: Option<u64>) -> PyResult<Self> {
         let max_workers = max_workers.unwrap_or_else(|| rayon::current_num_threads());
-        
+
         // Create a custom thread pool with the specified number of workers
-        let thread_pool = rayon::ThreadPoolBuilder::new()
-            .num_threads(max_workers)
-            .build()
-            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create thread pool: {}", e)))?;
-        
+        let thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(max_workers)
+                .build()
+                .map_err(|e| PyRuntimeError::new_err(format!("Failed to create thread pool: {}", e)))?,
+        );
+
+        let pending: Arc<Mutex<VecDeque<PendingSubmission>>> = Arc::new(Mutex::new(VecDeque::new()));
+        let buffered_count = Arc::new(AtomicUsize::new(0));
+        let dispatched_count = Arc::new(AtomicUsize::new(0));
+        let flush_running = Arc::new(AtomicBool::new(throttle_ms.is_some()));
+        let flush_notify = Arc::new((Mutex::new(false), Condvar::new()));
+
+        let flush_thread = throttle_ms.map(|interval_ms| {
+            let pending = Arc::clone(&pending);
+            let buffered_count = Arc::clone(&buffered_count);
+            let dispatched_count = Arc::clone(&dispatched_count);
+            let flush_running = Arc::clone(&flush_running);
+            let flush_notify = Arc::clone(&flush_notify);
+            let thread_pool = Arc::clone(&thread_pool);
+
+            thread::Builder::new()
+                .name("pyferris-executor-throttle".to_string())
+                .spawn(move || {
+                    while flush_running.load(Ordering::Relaxed) {
+                        let (lock, cvar) = &*flush_notify;
+                        let _ = cvar
+                            .wait_timeout(lock.lock().unwrap(), Duration::from_millis(interval_ms))
+                            .unwrap();
+                        Self::flush_pending(&pending, &buffered_count, &dispatched_count, &thread_pool);
+                    }
+                    // Drain whatever arrived between the last flush and shutdown.
+                    Self::flush_pending(&pending, &buffered_count, &dispatched_count, &thread_pool);
+                })
+                .expect("Failed to spawn throttle flush thread")
+        });
+
         Ok(Self {
             max_workers,
             thread_pool: Some(thread_pool),
+            chaos: None,
+            throttle_ms,
+            pending,
+            buffered_count,
+            dispatched_count,
+            flush_running,
+            flush_notify,
+            flush_thread,
         })
     }
 
-    /// Submit a single task with explicit arguments (runs immediately for compatibility)
-    pub fn submit_with_args(&self, func: Bound<PyAny>, args: Bound<PyTuple>) -> PyResult<PyObject> {
-        // For individual tasks, we run them immediately to maintain compatibility
-        // with concurrent.futures interface expectations
-        let result = func.call1(&args)?;
-        Ok(result.into())
+    /// Attach a chaos-testing config; subsequent `map` calls will probabilistically
+    /// inject latency and failures per item. Pass `None` to disable.
+    pub fn set_chaos_config(&mut self, chaos: Option<ChaosConfig>) {
+        self.chaos = chaos;
+    }
+
+    /// Submit a single task with explicit arguments onto the thread pool and
+    /// return a `PyFuture` immediately — the call runs on a worker thread (or
+    /// is buffered for the next throttled flush), not on the calling thread.
+    pub fn submit_with_args(&self, func: Bound<PyAny>, args: Bound<PyTuple>) -> PyResult<Py<PyFuture>> {
+        let py = func.py();
+        self.dispatch(py, func.unbind(), Some(args.unbind()))
     }
 
-    /// Submit a single task (for compatibility with asyncio.run_in_executor)
-    pub fn submit(&self, func: Bound<PyAny>) -> PyResult<PyObject> {
-        // For individual tasks, we run them immediately
-        let result = func.call0()?;
-        Ok(result.into())
+    /// Submit a single task onto the thread pool and return a `PyFuture`
+    /// immediately, so it integrates with `asyncio.run_in_executor` and
+    /// `concurrent.futures`-style code instead of blocking the caller.
+    pub fn submit(&self, func: Bound<PyAny>) -> PyResult<Py<PyFuture>> {
+        let py = func.py();
+        self.dispatch(py, func.unbind(), None)
     }
 
     /// Submit multiple tasks and collect results
@@ -50,13 +296,14 @@ impl Executor {
         let py = func.py();
         // Convert to PyObjects to avoid Sync issues
         let items: Vec<PyObject> = iterable.try_iter()?.map(|item| item.map(|i| i.into())).collect::<PyResult<Vec<_>>>()?;
-        
+
         if items.is_empty() {
             return Ok(PyList::empty(py).into());
         }
-        
+
         let func: Arc<PyObject> = Arc::new(func.into());
-        
+        let chaos = self.chaos.clone();
+
         // Use our custom thread pool if available, otherwise fall back to global pool
         let results: Vec<PyObject> = if let Some(ref pool) = self.thread_pool {
             py.allow_threads(|| {
@@ -64,6 +311,9 @@ impl Executor {
                     let chunk_results: PyResult<Vec<PyObject>> = items
                         .par_iter()
                         .map(|item| {
+                            if let Some(chaos) = &chaos {
+                                chaos.inject()?;
+                            }
                             Python::with_gil(|py| {
                                 let bound_item = item.bind(py);
                                 let bound_func = func.bind(py);
@@ -81,6 +331,9 @@ impl Executor {
                 let chunk_results: PyResult<Vec<PyObject>> = items
                     .par_iter()
                     .map(|item| {
+                        if let Some(chaos) = &chaos {
+                            chaos.inject()?;
+                        }
                         Python::with_gil(|py| {
                             let bound_item = item.bind(py);
                             let bound_func = func.bind(py);
@@ -107,8 +360,25 @@ impl Executor {
         self.thread_pool.is_some()
     }
 
+    /// Execution statistics: currently buffered (awaiting flush) and lifetime
+    /// dispatched counts for throttled submissions.
+    pub fn get_stats(&self) -> std::collections::HashMap<String, usize> {
+        let mut stats = std::collections::HashMap::new();
+        stats.insert("buffered".to_string(), self.buffered_count.load(Ordering::Relaxed));
+        stats.insert("dispatched".to_string(), self.dispatched_count.load(Ordering::Relaxed));
+        stats
+    }
+
     /// Shutdown the executor
     pub fn shutdown(&mut self) {
+        self.flush_running.store(false, Ordering::Relaxed);
+        let (lock, cvar) = &*self.flush_notify;
+        *lock.lock().unwrap() = true;
+        cvar.notify_all();
+        if let Some(handle) = self.flush_thread.take() {
+            let _ = handle.join();
+        }
+
         // Drop the thread pool to shut it down
         self.thread_pool = None;
     }
@@ -0,0 +1,96 @@
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Probabilistic fault injection for exercising `RetryExecutor` and `CircuitBreaker`
+/// logic without a flaky real backend. Attach a `ChaosConfig` to `DistributedExecutor`
+/// or `Executor` and each task execution rolls against `fail_probability` to raise
+/// `error_type`, and otherwise sleeps a random latency drawn from `latency_ms_range`
+/// before running. The RNG is seeded so a run with the same `seed` is reproducible.
+/// An `error_type` of `"TimeoutError"` or `"ConnectionError"` raises that exception;
+/// any other name raises `RuntimeError` with the name in its message.
+#[pyclass]
+#[derive(Clone)]
+pub struct ChaosConfig {
+    #[pyo3(get)]
+    pub fail_probability: f64,
+    #[pyo3(get)]
+    pub latency_ms_range: (u64, u64),
+    #[pyo3(get)]
+    pub error_type: String,
+    #[pyo3(get)]
+    pub seed: u64,
+    rng: Arc<Mutex<StdRng>>,
+}
+
+#[pymethods]
+impl ChaosConfig {
+    #[new]
+    #[pyo3(signature = (fail_probability=0.0, latency_ms_range=(0, 0), error_type=None, seed=None))]
+    pub fn new(
+        fail_probability: f64,
+        latency_ms_range: (u64, u64),
+        error_type: Option<String>,
+        seed: Option<u64>,
+    ) -> PyResult<Self> {
+        if !(0.0..=1.0).contains(&fail_probability) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "fail_probability must be between 0.0 and 1.0",
+            ));
+        }
+        if latency_ms_range.0 > latency_ms_range.1 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "latency_ms_range must be (min, max) with min <= max",
+            ));
+        }
+
+        let seed = seed.unwrap_or(0);
+        Ok(Self {
+            fail_probability,
+            latency_ms_range,
+            error_type: error_type.unwrap_or_else(|| "RuntimeError".to_string()),
+            seed,
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        })
+    }
+}
+
+impl ChaosConfig {
+    /// Roll the dice for one task execution: returns `Err(error_type)` if chaos
+    /// decided this call should fail, and otherwise sleeps a random latency within
+    /// `latency_ms_range`. Call this immediately before running the wrapped function.
+    pub fn inject(&self) -> PyResult<()> {
+        let (latency_ms, should_fail) = {
+            let mut rng = self.rng.lock().unwrap();
+            let latency_ms = if self.latency_ms_range.1 > 0 {
+                rng.gen_range(self.latency_ms_range.0..=self.latency_ms_range.1)
+            } else {
+                0
+            };
+            (latency_ms, rng.gen_bool(self.fail_probability))
+        };
+
+        if latency_ms > 0 && !should_fail {
+            thread::sleep(Duration::from_millis(latency_ms));
+        }
+
+        if should_fail {
+            return Err(match self.error_type.as_str() {
+                "TimeoutError" => {
+                    pyo3::exceptions::PyTimeoutError::new_err("chaos: injected timeout")
+                }
+                "ConnectionError" => pyo3::exceptions::PyConnectionError::new_err(
+                    "chaos: injected connection failure",
+                ),
+                other => {
+                    pyo3::exceptions::PyRuntimeError::new_err(format!("chaos: injected {}", other))
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -2,10 +2,20 @@ use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid;
 
+use super::codec::CheckpointCodec;
+
+/// A single entry in an operation log, replayed on top of a checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpLogEntry {
+    pub timestamp: u64,
+    pub payload: String,
+}
+
 /// Checkpoint data structure
 #[pyclass]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +58,19 @@ impl Checkpoint {
     }
 }
 
+/// Result of scanning the checkpoint directory for crash-consistency, returned by
+/// `CheckpointManager::recover`.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct CheckpointRecovery {
+    #[pyo3(get)]
+    pub recovered: Option<Checkpoint>,
+    #[pyo3(get)]
+    pub corrupt_removed: Vec<String>,
+    #[pyo3(get)]
+    pub incomplete_removed: Vec<String>,
+}
+
 /// Checkpoint manager for saving and restoring operation state
 #[pyclass]
 #[derive(Clone)]
@@ -55,19 +78,21 @@ pub struct CheckpointManager {
     checkpoint_dir: PathBuf,
     auto_save_interval: Option<u64>,
     max_checkpoints: usize,
+    codec: Option<CheckpointCodec>,
 }
 
 #[pymethods]
 impl CheckpointManager {
     #[new]
-    #[pyo3(signature = (checkpoint_dir, auto_save_interval=None, max_checkpoints=10))]
+    #[pyo3(signature = (checkpoint_dir, auto_save_interval=None, max_checkpoints=10, codec=None))]
     pub fn new(
         checkpoint_dir: String,
         auto_save_interval: Option<u64>,
-        max_checkpoints: usize
+        max_checkpoints: usize,
+        codec: Option<CheckpointCodec>,
     ) -> PyResult<Self> {
         let checkpoint_dir = PathBuf::from(checkpoint_dir);
-        
+
         // Create directory if it doesn't exist
         if !checkpoint_dir.exists() {
             fs::create_dir_all(&checkpoint_dir)
@@ -78,6 +103,7 @@ impl CheckpointManager {
             checkpoint_dir,
             auto_save_interval,
             max_checkpoints,
+            codec,
         })
     }
 
@@ -105,36 +131,269 @@ impl CheckpointManager {
             metadata
         );
 
-        let checkpoint_file = self.checkpoint_dir.join(format!("{}.json", checkpoint_id));
         let checkpoint_json = serde_json::to_string_pretty(&checkpoint)
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to serialize checkpoint: {}", e)))?;
 
-        fs::write(&checkpoint_file, checkpoint_json)
-            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to write checkpoint: {}", e)))?;
+        let bytes = match &self.codec {
+            Some(codec) => codec.encode(checkpoint_json.as_bytes())?,
+            None => checkpoint_json.into_bytes(),
+        };
+
+        self.write_atomic(&checkpoint_id, &bytes)?;
 
         // Clean up old checkpoints
         self.cleanup_old_checkpoints(&operation_id)?;
 
+        // Compact the operation log now that a new checkpoint exists
+        self.compact_oplog(&operation_id)?;
+
         Ok(checkpoint_id)
     }
 
+    /// Append an operation to the operation log for replay on top of the last checkpoint
+    pub fn append_operation(&self, operation_id: String, op_payload: String) -> PyResult<()> {
+        let entry = OpLogEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_micros() as u64,
+            payload: op_payload,
+        };
+        let entry_json = serde_json::to_string(&entry)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to serialize operation: {}", e)))?;
+
+        let oplog_file = self.oplog_path(&operation_id);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&oplog_file)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to open oplog: {}", e)))?;
+
+        writeln!(file, "{}", entry_json)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to append operation: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Restore state by loading the latest checkpoint and replaying newer logged operations
+    /// through a Python `reducer(state, op_payload) -> state` fold function.
+    pub fn restore_with_replay(&self, py: Python, operation_id: String, reducer: Py<PyAny>) -> PyResult<Py<PyAny>> {
+        let checkpoint = self.get_latest_checkpoint(operation_id.clone())?;
+
+        let (mut state, since) = match checkpoint {
+            Some(checkpoint) => {
+                let dict = pyo3::types::PyDict::new(py);
+                for (key, value) in &checkpoint.state {
+                    dict.set_item(key, value)?;
+                }
+                (dict.into_any().unbind(), checkpoint.timestamp)
+            }
+            None => (pyo3::types::PyDict::new(py).into_any().unbind(), 0),
+        };
+
+        for entry in self.read_oplog(&operation_id)? {
+            if entry.timestamp > since {
+                state = reducer.call1(py, (state, entry.payload))?;
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Path to the append-only operation log for an operation
+    fn oplog_path(&self, operation_id: &str) -> PathBuf {
+        self.checkpoint_dir.join(format!("{}.oplog", operation_id))
+    }
+
+    /// On-disk extension for a checkpoint file: plain `.json` when no codec is
+    /// configured (preserving the legacy plaintext format), or the framed `.ckpt`
+    /// format once compression and/or encryption is in play.
+    fn checkpoint_ext(&self) -> &'static str {
+        if self.codec.is_some() { "ckpt" } else { "json" }
+    }
+
+    /// Path to a checkpoint's on-disk file
+    fn checkpoint_path(&self, checkpoint_id: &str) -> PathBuf {
+        self.checkpoint_dir.join(format!("{}.{}", checkpoint_id, self.checkpoint_ext()))
+    }
+
+    /// Path to the temporary file a checkpoint is staged in before being renamed into place
+    fn checkpoint_tmp_path(&self, checkpoint_id: &str) -> PathBuf {
+        self.checkpoint_dir.join(format!("{}.{}.tmp", checkpoint_id, self.checkpoint_ext()))
+    }
+
+    /// Load and decode a checkpoint's raw bytes into its `Checkpoint` value, reversing
+    /// whatever compression/encryption the configured codec applied on write.
+    fn decode_checkpoint_file(&self, path: &std::path::Path) -> PyResult<Checkpoint> {
+        let raw = fs::read(path)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to read checkpoint: {}", e)))?;
+
+        let json_bytes = match &self.codec {
+            Some(codec) => codec.decode(&raw)?,
+            None => raw,
+        };
+
+        let json = String::from_utf8(json_bytes)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Checkpoint is not valid UTF-8: {}", e)))?;
+
+        serde_json::from_str(&json)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to deserialize checkpoint: {}", e)))
+    }
+
+    /// Write a checkpoint's bytes crash-safely: write to a `.tmp` file, fsync, then rename
+    /// into place so readers never observe a partially-written checkpoint.
+    fn write_atomic(&self, checkpoint_id: &str, bytes: &[u8]) -> PyResult<()> {
+        let tmp_path = self.checkpoint_tmp_path(checkpoint_id);
+        let final_path = self.checkpoint_path(checkpoint_id);
+
+        let mut file = fs::File::create(&tmp_path)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to create checkpoint tmp file: {}", e)))?;
+        file.write_all(bytes)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to write checkpoint: {}", e)))?;
+        file.sync_all()
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to fsync checkpoint: {}", e)))?;
+        drop(file);
+
+        fs::rename(&tmp_path, &final_path)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to finalize checkpoint: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Scan the checkpoint directory for an operation, classify each checkpoint file as
+    /// valid, corrupt, or an incomplete leftover `.tmp`, discard the bad ones, and return
+    /// the newest valid checkpoint alongside a report of what was removed.
+    #[pyo3(signature = (operation_id, should_recover=None))]
+    pub fn recover(&self, operation_id: String, should_recover: Option<Py<PyAny>>) -> PyResult<CheckpointRecovery> {
+        let mut valid = Vec::new();
+        let mut corrupt_removed = Vec::new();
+        let mut incomplete_removed = Vec::new();
+
+        let entries = fs::read_dir(&self.checkpoint_dir)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to read checkpoint directory: {}", e)))?;
+
+        let tmp_suffix = format!(".{}.tmp", self.checkpoint_ext());
+
+        for entry in entries {
+            let entry = entry.map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))?;
+            let path = entry.path();
+            let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+
+            if file_name.ends_with(&tmp_suffix) {
+                fs::remove_file(&path).ok();
+                incomplete_removed.push(file_name);
+                continue;
+            }
+
+            if path.extension().and_then(|s| s.to_str()) != Some(self.checkpoint_ext()) {
+                continue;
+            }
+
+            match self.decode_checkpoint_file(&path) {
+                Ok(checkpoint) if checkpoint.operation == operation_id => valid.push(checkpoint),
+                Ok(_) => {} // belongs to a different operation, leave it alone
+                Err(_) => {
+                    fs::remove_file(&path).ok();
+                    corrupt_removed.push(file_name);
+                }
+            }
+        }
+
+        valid.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+        let mut recovered = None;
+        for checkpoint in valid {
+            let accept = match &should_recover {
+                Some(callback) => Python::attach(|py| {
+                    callback
+                        .call1(py, (checkpoint.clone(),))
+                        .and_then(|result| result.bind(py).is_truthy())
+                        .unwrap_or(false)
+                }),
+                None => true,
+            };
+            if accept {
+                recovered = Some(checkpoint);
+                break;
+            }
+        }
+
+        Ok(CheckpointRecovery {
+            recovered,
+            corrupt_removed,
+            incomplete_removed,
+        })
+    }
+
+    /// Read all operation log entries for an operation, in append order
+    fn read_oplog(&self, operation_id: &str) -> PyResult<Vec<OpLogEntry>> {
+        let oplog_file = self.oplog_path(operation_id);
+        if !oplog_file.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = fs::File::open(&oplog_file)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to open oplog: {}", e)))?;
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to read oplog: {}", e)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Ok(entry) = serde_json::from_str::<OpLogEntry>(&line) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Drop oplog entries older than the two most recent checkpoints for this operation,
+    /// so a compaction never discards ops that a concurrent reader might still need.
+    fn compact_oplog(&self, operation_id: &str) -> PyResult<()> {
+        let mut checkpoints = self.list_checkpoints(Some(operation_id.to_string()))?;
+        if checkpoints.len() < 2 {
+            return Ok(());
+        }
+        checkpoints.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        let keep_since = checkpoints[1].timestamp;
+
+        let entries: Vec<OpLogEntry> = self
+            .read_oplog(operation_id)?
+            .into_iter()
+            .filter(|entry| entry.timestamp >= keep_since)
+            .collect();
+
+        let oplog_file = self.oplog_path(operation_id);
+        if !oplog_file.exists() {
+            return Ok(());
+        }
+
+        let mut contents = String::new();
+        for entry in &entries {
+            let entry_json = serde_json::to_string(entry)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to serialize operation: {}", e)))?;
+            contents.push_str(&entry_json);
+            contents.push('\n');
+        }
+
+        fs::write(&oplog_file, contents)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to compact oplog: {}", e)))?;
+
+        Ok(())
+    }
+
     /// Restore a checkpoint by ID
     pub fn restore_checkpoint(&self, checkpoint_id: String) -> PyResult<Checkpoint> {
-        let checkpoint_file = self.checkpoint_dir.join(format!("{}.json", checkpoint_id));
-        
+        let checkpoint_file = self.checkpoint_path(&checkpoint_id);
+
         if !checkpoint_file.exists() {
             return Err(pyo3::exceptions::PyFileNotFoundError::new_err(
                 format!("Checkpoint {} not found", checkpoint_id)
             ));
         }
 
-        let checkpoint_data = fs::read_to_string(&checkpoint_file)
-            .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to read checkpoint: {}", e)))?;
-
-        let checkpoint: Checkpoint = serde_json::from_str(&checkpoint_data)
-            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to deserialize checkpoint: {}", e)))?;
-
-        Ok(checkpoint)
+        self.decode_checkpoint_file(&checkpoint_file)
     }
 
     /// Get the latest checkpoint for an operation
@@ -154,12 +413,9 @@ impl CheckpointManager {
         for entry in entries {
             let entry = entry.map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))?;
             let path = entry.path();
-            
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                let checkpoint_data = fs::read_to_string(&path)
-                    .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to read checkpoint: {}", e)))?;
 
-                if let Ok(checkpoint) = serde_json::from_str::<Checkpoint>(&checkpoint_data) {
+            if path.extension().and_then(|s| s.to_str()) == Some(self.checkpoint_ext()) {
+                if let Ok(checkpoint) = self.decode_checkpoint_file(&path) {
                     if operation_id.is_none() || operation_id.as_ref() == Some(&checkpoint.operation) {
                         checkpoints.push(checkpoint);
                     }
@@ -174,8 +430,8 @@ impl CheckpointManager {
 
     /// Delete a checkpoint
     pub fn delete_checkpoint(&self, checkpoint_id: String) -> PyResult<bool> {
-        let checkpoint_file = self.checkpoint_dir.join(format!("{}.json", checkpoint_id));
-        
+        let checkpoint_file = self.checkpoint_path(&checkpoint_id);
+
         if checkpoint_file.exists() {
             fs::remove_file(&checkpoint_file)
                 .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("Failed to delete checkpoint: {}", e)))?;
@@ -225,26 +481,56 @@ pub struct AutoCheckpoint {
     operation_id: String,
     checkpoint_manager: CheckpointManager,
     interval_seconds: u64,
+    min_ops: u64,
+    min_bytes: u64,
+    max_ops: Option<u64>,
+    max_bytes: Option<u64>,
     last_checkpoint: std::sync::Mutex<Option<SystemTime>>,
+    ops_since_checkpoint: std::sync::atomic::AtomicU64,
+    bytes_since_checkpoint: std::sync::atomic::AtomicU64,
 }
 
 #[pymethods]
 impl AutoCheckpoint {
     #[new]
+    #[pyo3(signature = (operation_id, checkpoint_manager, interval_seconds, min_ops=0, min_bytes=0, max_ops=None, max_bytes=None))]
     pub fn new(
         operation_id: String,
         checkpoint_manager: CheckpointManager,
-        interval_seconds: u64
+        interval_seconds: u64,
+        min_ops: u64,
+        min_bytes: u64,
+        max_ops: Option<u64>,
+        max_bytes: Option<u64>,
     ) -> Self {
         Self {
             operation_id,
             checkpoint_manager,
             interval_seconds,
+            min_ops,
+            min_bytes,
+            max_ops,
+            max_bytes,
             last_checkpoint: std::sync::Mutex::new(None),
+            ops_since_checkpoint: std::sync::atomic::AtomicU64::new(0),
+            bytes_since_checkpoint: std::sync::atomic::AtomicU64::new(0),
         }
     }
 
-    /// Maybe create a checkpoint if enough time has passed
+    /// Record that `n` operations have happened since the last checkpoint
+    pub fn record_ops(&self, n: u64) -> u64 {
+        self.ops_since_checkpoint.fetch_add(n, std::sync::atomic::Ordering::Relaxed) + n
+    }
+
+    /// Record that `n` bytes of work have happened since the last checkpoint
+    pub fn record_bytes(&self, n: u64) -> u64 {
+        self.bytes_since_checkpoint.fetch_add(n, std::sync::atomic::Ordering::Relaxed) + n
+    }
+
+    /// Maybe create a checkpoint: fires when the interval has elapsed AND the volume
+    /// guard (`min_ops`/`min_bytes`) is met, or immediately once a `max_ops`/`max_bytes`
+    /// cap is reached regardless of elapsed time. A minimum left at 0 is not part of
+    /// the guard; with both at 0 the guard always passes.
     pub fn maybe_checkpoint(
         &self,
         state_data: HashMap<String, String>,
@@ -252,8 +538,11 @@ impl AutoCheckpoint {
     ) -> PyResult<Option<String>> {
         let now = SystemTime::now();
         let mut last_checkpoint = self.last_checkpoint.lock().unwrap();
-        
-        let should_checkpoint = match *last_checkpoint {
+
+        let ops = self.ops_since_checkpoint.load(std::sync::atomic::Ordering::Relaxed);
+        let bytes = self.bytes_since_checkpoint.load(std::sync::atomic::Ordering::Relaxed);
+
+        let interval_elapsed = match *last_checkpoint {
             None => true,
             Some(last) => {
                 now.duration_since(last)
@@ -261,6 +550,13 @@ impl AutoCheckpoint {
                     .as_secs() >= self.interval_seconds
             }
         };
+        let volume_met = (self.min_ops == 0 && self.min_bytes == 0)
+            || (self.min_ops > 0 && ops >= self.min_ops)
+            || (self.min_bytes > 0 && bytes >= self.min_bytes);
+        let volume_forced = self.max_ops.is_some_and(|max| ops >= max)
+            || self.max_bytes.is_some_and(|max| bytes >= max);
+
+        let should_checkpoint = (interval_elapsed && volume_met) || volume_forced;
 
         if should_checkpoint {
             let checkpoint_id = self.checkpoint_manager.save_checkpoint(
@@ -270,6 +566,8 @@ impl AutoCheckpoint {
                 None
             )?;
             *last_checkpoint = Some(now);
+            self.ops_since_checkpoint.store(0, std::sync::atomic::Ordering::Relaxed);
+            self.bytes_since_checkpoint.store(0, std::sync::atomic::Ordering::Relaxed);
             Ok(Some(checkpoint_id))
         } else {
             Ok(None)
@@ -291,7 +589,9 @@ impl AutoCheckpoint {
         
         let mut last_checkpoint = self.last_checkpoint.lock().unwrap();
         *last_checkpoint = Some(SystemTime::now());
-        
+        self.ops_since_checkpoint.store(0, std::sync::atomic::Ordering::Relaxed);
+        self.bytes_since_checkpoint.store(0, std::sync::atomic::Ordering::Relaxed);
+
         Ok(checkpoint_id)
     }
 }
\ No newline at end of file
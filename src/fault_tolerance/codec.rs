@@ -0,0 +1,190 @@
+use pyo3::prelude::*;
+use rand::RngCore;
+
+/// 4-byte magic identifying a framed, possibly compressed/encrypted checkpoint blob
+const MAGIC: &[u8; 4] = b"PFCK";
+/// Current on-disk format version; bumping this lets future readers reject or
+/// migrate blobs written by an older codec
+const VERSION: u8 = 1;
+
+const FLAG_GZIP: u8 = 0b0000_0001;
+const FLAG_ZSTD: u8 = 0b0000_0010;
+const FLAG_ENCRYPTED: u8 = 0b0000_0100;
+
+const NONCE_LEN: usize = 24; // XChaCha20-Poly1305 extended nonce
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompressionKind {
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// Optional compression and symmetric encryption applied to checkpoint bytes before
+/// they hit disk. Serialize-then-compress-then-encrypt on write, the reverse on read.
+/// Every blob carries a small magic/version header so a truncated or tampered file
+/// fails with a clear error instead of a confusing JSON parse error.
+#[pyclass]
+#[derive(Clone)]
+pub struct CheckpointCodec {
+    compression: CompressionKind,
+    encryption_key: Option<[u8; 32]>,
+}
+
+#[pymethods]
+impl CheckpointCodec {
+    #[new]
+    #[pyo3(signature = (compression=None, encryption_key=None))]
+    pub fn new(compression: Option<String>, encryption_key: Option<Vec<u8>>) -> PyResult<Self> {
+        let compression = match compression.as_deref() {
+            None | Some("none") => CompressionKind::None,
+            Some("gzip") => CompressionKind::Gzip,
+            Some("zstd") => CompressionKind::Zstd,
+            Some(other) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown compression algorithm '{}', expected 'gzip', 'zstd' or 'none'",
+                    other
+                )))
+            }
+        };
+
+        let encryption_key = match encryption_key {
+            None => None,
+            Some(key) => {
+                let key: [u8; 32] = key.try_into().map_err(|_| {
+                    pyo3::exceptions::PyValueError::new_err("encryption_key must be exactly 32 bytes")
+                })?;
+                Some(key)
+            }
+        };
+
+        Ok(Self { compression, encryption_key })
+    }
+}
+
+impl CheckpointCodec {
+    /// Serialize-ready bytes in, framed/compressed/encrypted bytes out
+    pub fn encode(&self, plaintext: &[u8]) -> PyResult<Vec<u8>> {
+        let compressed = match self.compression {
+            CompressionKind::None => plaintext.to_vec(),
+            CompressionKind::Gzip => {
+                use flate2::write::GzEncoder;
+                use flate2::Compression;
+                use std::io::Write as _;
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(plaintext)
+                    .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("gzip compression failed: {}", e)))?;
+                encoder
+                    .finish()
+                    .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("gzip compression failed: {}", e)))?
+            }
+            CompressionKind::Zstd => zstd::stream::encode_all(plaintext, 0)
+                .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("zstd compression failed: {}", e)))?,
+        };
+
+        let mut flags = match self.compression {
+            CompressionKind::None => 0,
+            CompressionKind::Gzip => FLAG_GZIP,
+            CompressionKind::Zstd => FLAG_ZSTD,
+        };
+
+        let mut body = Vec::with_capacity(compressed.len() + NONCE_LEN);
+        if let Some(key) = &self.encryption_key {
+            use chacha20poly1305::aead::{Aead, KeyInit};
+            use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+            flags |= FLAG_ENCRYPTED;
+
+            let mut nonce_bytes = [0u8; NONCE_LEN];
+            rand::thread_rng().fill_bytes(&mut nonce_bytes);
+            let nonce = XNonce::from_slice(&nonce_bytes);
+
+            let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+            let ciphertext = cipher
+                .encrypt(nonce, compressed.as_ref())
+                .map_err(|_| pyo3::exceptions::PyValueError::new_err("Checkpoint encryption failed"))?;
+
+            body.extend_from_slice(&nonce_bytes);
+            body.extend_from_slice(&ciphertext);
+        } else {
+            body.extend_from_slice(&compressed);
+        }
+
+        let mut framed = Vec::with_capacity(MAGIC.len() + 2 + body.len());
+        framed.extend_from_slice(MAGIC);
+        framed.push(VERSION);
+        framed.push(flags);
+        framed.extend_from_slice(&body);
+        Ok(framed)
+    }
+
+    /// Reverse of `encode`: validates the header, decrypts and decompresses, and
+    /// returns the original plaintext bytes.
+    pub fn decode(&self, framed: &[u8]) -> PyResult<Vec<u8>> {
+        if framed.len() < MAGIC.len() + 2 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Checkpoint file is truncated: missing header",
+            ));
+        }
+        if &framed[..MAGIC.len()] != MAGIC {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Checkpoint file has an invalid magic header (not a Pyferris checkpoint, or corrupted)",
+            ));
+        }
+
+        let version = framed[MAGIC.len()];
+        if version != VERSION {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unsupported checkpoint format version {}",
+                version
+            )));
+        }
+
+        let flags = framed[MAGIC.len() + 1];
+        let mut body = &framed[MAGIC.len() + 2..];
+
+        let decrypted;
+        if flags & FLAG_ENCRYPTED != 0 {
+            use chacha20poly1305::aead::{Aead, KeyInit};
+            use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+            let key = self.encryption_key.ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err("Checkpoint is encrypted but no encryption_key was configured")
+            })?;
+
+            if body.len() < NONCE_LEN {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "Checkpoint file is truncated: missing encryption nonce",
+                ));
+            }
+            let (nonce_bytes, ciphertext) = body.split_at(NONCE_LEN);
+            let nonce = XNonce::from_slice(nonce_bytes);
+            let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+
+            decrypted = cipher.decrypt(nonce, ciphertext).map_err(|_| {
+                pyo3::exceptions::PyValueError::new_err(
+                    "Checkpoint decryption failed: wrong key or the file was tampered with",
+                )
+            })?;
+            body = &decrypted;
+        }
+
+        let plaintext = if flags & FLAG_GZIP != 0 {
+            use flate2::read::GzDecoder;
+            use std::io::Read as _;
+            let mut out = Vec::new();
+            GzDecoder::new(body)
+                .read_to_end(&mut out)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("gzip decompression failed: {}", e)))?;
+            out
+        } else if flags & FLAG_ZSTD != 0 {
+            zstd::stream::decode_all(body)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("zstd decompression failed: {}", e)))?
+        } else {
+            body.to_vec()
+        };
+
+        Ok(plaintext)
+    }
+}
@@ -0,0 +1,9 @@
+pub mod chaos;
+pub mod checkpoint;
+pub mod codec;
+pub mod retry;
+
+pub use chaos::*;
+pub use checkpoint::*;
+pub use codec::*;
+pub use retry::*;
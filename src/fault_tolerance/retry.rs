@@ -1,5 +1,9 @@
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyFunction, PyTuple};
+use rand::Rng;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -18,6 +22,32 @@ pub enum RetryStrategy {
     },
 }
 
+/// How the deterministic backoff delay is perturbed before sleeping, to avoid a
+/// thundering herd of clients retrying a recovering service in lockstep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JitterMode {
+    /// No randomization; use the deterministic backoff delay as-is
+    None,
+    /// Uniform random value in `[0, min(max, deterministic_delay))`
+    Full,
+    /// `min(max, uniform(initial, prev_sleep * 3))`, carrying `prev_sleep` across attempts
+    Decorrelated,
+}
+
+impl JitterMode {
+    fn from_name(name: Option<&str>) -> PyResult<Self> {
+        match name {
+            None | Some("none") => Ok(JitterMode::None),
+            Some("full") => Ok(JitterMode::Full),
+            Some("decorrelated") => Ok(JitterMode::Decorrelated),
+            Some(other) => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown jitter mode '{}', expected 'none', 'full' or 'decorrelated'",
+                other
+            ))),
+        }
+    }
+}
+
 /// Retry executor for handling transient failures
 #[pyclass]
 pub struct RetryExecutor {
@@ -25,6 +55,10 @@ pub struct RetryExecutor {
     max_attempts: usize,
     exceptions_to_retry: Vec<String>,
     on_retry_callback: Option<Py<PyFunction>>,
+    jitter: JitterMode,
+    base_delay: Duration,
+    jitter_cap: Duration,
+    prev_sleep: Mutex<Duration>,
 }
 
 impl Clone for RetryExecutor {
@@ -34,6 +68,10 @@ impl Clone for RetryExecutor {
             max_attempts: self.max_attempts,
             exceptions_to_retry: self.exceptions_to_retry.clone(),
             on_retry_callback: None, // Cannot clone Py<PyFunction>, so reset to None
+            jitter: self.jitter,
+            base_delay: self.base_delay,
+            jitter_cap: self.jitter_cap,
+            prev_sleep: Mutex::new(*self.prev_sleep.lock().unwrap()),
         }
     }
 }
@@ -41,7 +79,7 @@ impl Clone for RetryExecutor {
 #[pymethods]
 impl RetryExecutor {
     #[new]
-    #[pyo3(signature = (max_attempts=3, strategy="fixed", initial_delay=1.0, max_delay=None, multiplier=None, increment=None))]
+    #[pyo3(signature = (max_attempts=3, strategy="fixed", initial_delay=1.0, max_delay=None, multiplier=None, increment=None, jitter=None))]
     pub fn new(
         max_attempts: usize,
         strategy: &str,
@@ -49,7 +87,8 @@ impl RetryExecutor {
         max_delay: Option<f64>,
         multiplier: Option<f64>,
         increment: Option<f64>,
-    ) -> Self {
+        jitter: Option<String>,
+    ) -> PyResult<Self> {
         let strategy = match strategy {
             "exponential" => RetryStrategy::ExponentialBackoff {
                 initial: Duration::from_secs_f64(initial_delay),
@@ -62,8 +101,10 @@ impl RetryExecutor {
             },
             _ => RetryStrategy::FixedDelay(Duration::from_secs_f64(initial_delay)),
         };
+        let jitter = JitterMode::from_name(jitter.as_deref())?;
+        let base_delay = Duration::from_secs_f64(initial_delay);
 
-        Self {
+        Ok(Self {
             strategy,
             max_attempts,
             exceptions_to_retry: vec![
@@ -72,7 +113,11 @@ impl RetryExecutor {
                 "Error".to_string(),
             ],
             on_retry_callback: None,
-        }
+            jitter,
+            base_delay,
+            jitter_cap: Duration::from_secs_f64(max_delay.unwrap_or(60.0)),
+            prev_sleep: Mutex::new(base_delay),
+        })
     }
 
     /// Set retry callback function
@@ -106,10 +151,16 @@ impl RetryExecutor {
 
                     last_error = Some(err);
 
-                    // Calculate delay for this attempt
+                    // Calculate delay for this attempt, with the configured jitter
+                    // applied
                     let delay = self.calculate_delay(attempt);
 
-                    // Call retry callback if set (simplified - just sleep for now)
+                    if let Some(callback) = &self.on_retry_callback {
+                        Python::attach(|py| {
+                            let _ = callback.call1(py, (attempt, delay.as_secs_f64()));
+                        });
+                    }
+
                     thread::sleep(delay);
                 }
             }
@@ -127,6 +178,7 @@ impl RetryExecutor {
         stats.set_item("max_attempts", self.max_attempts)?;
         stats.set_item("strategy", format!("{:?}", self.strategy))?;
         stats.set_item("retryable_exceptions", &self.exceptions_to_retry)?;
+        stats.set_item("jitter", format!("{:?}", self.jitter).to_lowercase())?;
         Ok(stats)
     }
 }
@@ -147,7 +199,7 @@ impl RetryExecutor {
     }
 
     fn calculate_delay(&self, attempt: usize) -> Duration {
-        match &self.strategy {
+        let deterministic = match &self.strategy {
             RetryStrategy::FixedDelay(delay) => *delay,
             RetryStrategy::ExponentialBackoff {
                 initial,
@@ -160,19 +212,115 @@ impl RetryExecutor {
             RetryStrategy::LinearBackoff { initial, increment } => {
                 *initial + *increment * attempt as u32
             }
+        };
+
+        self.apply_jitter(deterministic)
+    }
+
+    /// Perturb a deterministic backoff delay according to `self.jitter`. For `Full`
+    /// the deterministic delay (clamped to `max_delay`) is the upper bound;
+    /// `Decorrelated` does not use it and draws from its own `prev_sleep` state,
+    /// bounded by `max_delay`.
+    fn apply_jitter(&self, deterministic: Duration) -> Duration {
+        match self.jitter {
+            JitterMode::None => deterministic,
+            JitterMode::Full => {
+                let cap = deterministic.min(self.jitter_cap);
+                if cap.is_zero() {
+                    return cap;
+                }
+                Duration::from_secs_f64(rand::thread_rng().gen_range(0.0..cap.as_secs_f64()))
+            }
+            JitterMode::Decorrelated => {
+                let mut prev_sleep = self.prev_sleep.lock().unwrap();
+                let lo = self.base_delay.as_secs_f64();
+                let hi = (prev_sleep.as_secs_f64() * 3.0).max(lo + f64::EPSILON);
+                let next = rand::thread_rng()
+                    .gen_range(lo..hi)
+                    .min(self.jitter_cap.as_secs_f64());
+                let next = Duration::from_secs_f64(next);
+                *prev_sleep = next;
+                next
+            }
+        }
+    }
+}
+
+/// Rolling record of recent call outcomes backing the failure-rate calculation.
+/// `true` means the call failed.
+struct FailureWindow {
+    outcomes: VecDeque<bool>,
+    failures: usize,
+    capacity: usize,
+}
+
+impl FailureWindow {
+    fn new(capacity: usize) -> Self {
+        Self {
+            outcomes: VecDeque::with_capacity(capacity),
+            failures: 0,
+            capacity,
+        }
+    }
+
+    fn record(&mut self, failed: bool) {
+        if self.outcomes.len() == self.capacity {
+            if let Some(evicted) = self.outcomes.pop_front() {
+                if evicted {
+                    self.failures -= 1;
+                }
+            }
+        }
+        self.outcomes.push_back(failed);
+        if failed {
+            self.failures += 1;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.outcomes.clear();
+        self.failures = 0;
+    }
+
+    fn failure_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            0.0
+        } else {
+            self.failures as f64 / self.outcomes.len() as f64
         }
     }
 }
 
 /// Circuit breaker implementation
 #[pyclass]
-#[derive(Clone)]
 pub struct CircuitBreaker {
-    failure_threshold: usize,
+    failure_rate_threshold: f64,
+    minimum_samples: usize,
     recovery_timeout: Duration,
-    failure_count: Arc<Mutex<usize>>,
+    half_open_max_calls: usize,
+    success_threshold: usize,
+    window: Arc<Mutex<FailureWindow>>,
     last_failure: Arc<Mutex<Option<Instant>>>,
     state: Arc<Mutex<CircuitState>>,
+    half_open_permits: Arc<AtomicUsize>,
+    half_open_successes: Arc<Mutex<usize>>,
+}
+
+impl Clone for CircuitBreaker {
+    fn clone(&self) -> Self {
+        Self {
+            failure_rate_threshold: self.failure_rate_threshold,
+            minimum_samples: self.minimum_samples,
+            recovery_timeout: self.recovery_timeout,
+            half_open_max_calls: self.half_open_max_calls,
+            success_threshold: self.success_threshold,
+            window: Arc::clone(&self.window),
+            last_failure: Arc::clone(&self.last_failure),
+            state: Arc::clone(&self.state),
+            half_open_permits: Arc::clone(&self.half_open_permits),
+            half_open_successes: Arc::clone(&self.half_open_successes),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -185,14 +333,36 @@ enum CircuitState {
 #[pymethods]
 impl CircuitBreaker {
     #[new]
-    pub fn new(failure_threshold: Option<usize>, recovery_timeout: Option<f64>) -> Self {
-        Self {
-            failure_threshold: failure_threshold.unwrap_or(5),
+    #[pyo3(signature = (failure_threshold=None, recovery_timeout=None, window_size=None, failure_rate_threshold=None, minimum_samples=None, half_open_max_calls=None, success_threshold=None))]
+    pub fn new(
+        failure_threshold: Option<usize>,
+        recovery_timeout: Option<f64>,
+        window_size: Option<usize>,
+        failure_rate_threshold: Option<f64>,
+        minimum_samples: Option<usize>,
+        half_open_max_calls: Option<usize>,
+        success_threshold: Option<usize>,
+    ) -> PyResult<Self> {
+        let window_size = window_size.unwrap_or_else(|| failure_threshold.unwrap_or(10).max(10));
+        let failure_rate_threshold = failure_rate_threshold.unwrap_or(0.5);
+        if !(0.0..=1.0).contains(&failure_rate_threshold) {
+            return Err(PyValueError::new_err(
+                "failure_rate_threshold must be between 0.0 and 1.0",
+            ));
+        }
+
+        Ok(Self {
+            failure_rate_threshold,
+            minimum_samples: minimum_samples.unwrap_or(failure_threshold.unwrap_or(5)).max(1),
             recovery_timeout: Duration::from_secs_f64(recovery_timeout.unwrap_or(60.0)),
-            failure_count: Arc::new(Mutex::new(0)),
+            half_open_max_calls: half_open_max_calls.unwrap_or(1).max(1),
+            success_threshold: success_threshold.unwrap_or(1).max(1),
+            window: Arc::new(Mutex::new(FailureWindow::new(window_size))),
             last_failure: Arc::new(Mutex::new(None)),
             state: Arc::new(Mutex::new(CircuitState::Closed)),
-        }
+            half_open_permits: Arc::new(AtomicUsize::new(0)),
+            half_open_successes: Arc::new(Mutex::new(0)),
+        })
     }
 
     /// Execute function with circuit breaker protection
@@ -202,8 +372,8 @@ impl CircuitBreaker {
         function: Bound<'_, PyFunction>,
         args: Bound<'_, PyTuple>,
     ) -> PyResult<Py<PyAny>> {
-        // Check current state
-        {
+        // Check current state and, for half-open, claim a trial permit
+        let admitted_half_open = {
             let mut state = self.state.lock().unwrap();
             let last_failure = *self.last_failure.lock().unwrap();
 
@@ -213,38 +383,86 @@ impl CircuitBreaker {
                     if let Some(last_fail) = last_failure {
                         if last_fail.elapsed() >= self.recovery_timeout {
                             *state = CircuitState::HalfOpen;
+                            self.half_open_permits.store(0, Ordering::SeqCst);
+                            *self.half_open_successes.lock().unwrap() = 0;
+                            // The call that triggers the transition is itself the first
+                            // trial, so it must claim a half-open permit like any other
+                            // probe instead of running as an ordinary call.
+                            true
                         } else {
-                            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                            return Err(PyRuntimeError::new_err(
                                 "Circuit breaker is open - failing fast",
                             ));
                         }
+                    } else {
+                        false
                     }
                 }
-                CircuitState::HalfOpen => {
-                    // Allow limited testing
+                CircuitState::Closed => false,
+                CircuitState::HalfOpen => true,
+            }
+        };
+
+        if admitted_half_open {
+            // Admit only `half_open_max_calls` trial requests at a time
+            let mut permits = self.half_open_permits.load(Ordering::SeqCst);
+            loop {
+                if permits >= self.half_open_max_calls {
+                    return Err(PyRuntimeError::new_err(
+                        "Circuit breaker is half-open - trial call limit reached",
+                    ));
                 }
-                CircuitState::Closed => {
-                    // Normal operation
+                match self.half_open_permits.compare_exchange(
+                    permits,
+                    permits + 1,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                ) {
+                    Ok(_) => break,
+                    Err(current) => permits = current,
                 }
             }
         }
 
         // Execute the function
-        match function.call1(&args) {
-            Ok(result) => {
-                // Success - reset failure count and close circuit
-                *self.failure_count.lock().unwrap() = 0;
-                *self.state.lock().unwrap() = CircuitState::Closed;
-                Ok(result.unbind())
+        let result = function.call1(&args);
+
+        if admitted_half_open {
+            self.half_open_permits.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        match result {
+            Ok(value) => {
+                self.window.lock().unwrap().record(false);
+
+                if admitted_half_open {
+                    let mut successes = self.half_open_successes.lock().unwrap();
+                    *successes += 1;
+                    if *successes >= self.success_threshold {
+                        // Service recovered - close the circuit and reset the window
+                        *self.state.lock().unwrap() = CircuitState::Closed;
+                        self.window.lock().unwrap().clear();
+                        *successes = 0;
+                    }
+                }
+
+                Ok(value.unbind())
             }
             Err(err) => {
-                // Failure - increment count and potentially open circuit
-                let mut failure_count = self.failure_count.lock().unwrap();
-                *failure_count += 1;
+                self.window.lock().unwrap().record(true);
                 *self.last_failure.lock().unwrap() = Some(Instant::now());
 
-                if *failure_count >= self.failure_threshold {
+                if admitted_half_open {
+                    // Any half-open failure immediately re-opens the circuit
                     *self.state.lock().unwrap() = CircuitState::Open;
+                    *self.half_open_successes.lock().unwrap() = 0;
+                } else {
+                    let window = self.window.lock().unwrap();
+                    if window.outcomes.len() >= self.minimum_samples
+                        && window.failure_rate() >= self.failure_rate_threshold
+                    {
+                        *self.state.lock().unwrap() = CircuitState::Open;
+                    }
                 }
 
                 Err(err)
@@ -256,7 +474,7 @@ impl CircuitBreaker {
     pub fn get_status<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, pyo3::types::PyDict>> {
         let stats = pyo3::types::PyDict::new(py);
         let state = self.state.lock().unwrap();
-        let failure_count = *self.failure_count.lock().unwrap();
+        let window = self.window.lock().unwrap();
 
         let state_str = match *state {
             CircuitState::Closed => "closed",
@@ -265,16 +483,24 @@ impl CircuitBreaker {
         };
 
         stats.set_item("state", state_str)?;
-        stats.set_item("failure_count", failure_count)?;
-        stats.set_item("failure_threshold", self.failure_threshold)?;
+        stats.set_item("failure_rate", window.failure_rate())?;
+        stats.set_item("sample_count", window.outcomes.len())?;
+        stats.set_item("failure_rate_threshold", self.failure_rate_threshold)?;
+        stats.set_item(
+            "half_open_permits_in_use",
+            self.half_open_permits.load(Ordering::SeqCst),
+        )?;
+        stats.set_item("half_open_max_calls", self.half_open_max_calls)?;
         Ok(stats)
     }
 
     /// Reset circuit breaker to closed state
     pub fn reset(&self) -> PyResult<()> {
-        *self.failure_count.lock().unwrap() = 0;
+        self.window.lock().unwrap().clear();
         *self.last_failure.lock().unwrap() = None;
         *self.state.lock().unwrap() = CircuitState::Closed;
+        self.half_open_permits.store(0, Ordering::SeqCst);
+        *self.half_open_successes.lock().unwrap() = 0;
         Ok(())
     }
 }
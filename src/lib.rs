@@ -1,4 +1,8 @@
+// Note: the `mem-profiling` global allocator is installed by `profiling::alloc` itself
+// (behind `#[cfg(feature = "mem-profiling")]`), so it takes priority over jemalloc/mimalloc
+// below whenever that feature is enabled.
 #[cfg(not(any(
+    feature = "mem-profiling",
     target_env = "musl",
     target_os = "freebsd",
     target_os = "openbsd",
@@ -8,7 +12,7 @@
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
-#[cfg(feature = "mimalloc")]
+#[cfg(all(feature = "mimalloc", not(feature = "mem-profiling")))]
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
@@ -30,6 +34,7 @@ mod memory;
 mod profiling;
 mod distributed;
 mod fault_tolerance;
+mod virtual_thread;
 
 use core::*;
 use executor::*;
@@ -46,6 +51,7 @@ use memory::*;
 use profiling::*;
 use distributed::*;
 use fault_tolerance::*;
+use virtual_thread::*;
 
 /// Pyferris Rust Extensions
 /// High-performance Rust implementations
@@ -56,9 +62,15 @@ fn _pyferris(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(parallel_starmap, m)?)?;
     m.add_function(wrap_pyfunction!(parallel_filter, m)?)?;
     m.add_function(wrap_pyfunction!(parallel_reduce, m)?)?;
-    
+    m.add_function(wrap_pyfunction!(parallel_any, m)?)?;
+    m.add_function(wrap_pyfunction!(parallel_all, m)?)?;
+    m.add_function(wrap_pyfunction!(parallel_find_any, m)?)?;
+    m.add_function(wrap_pyfunction!(parallel_find_first, m)?)?;
+    m.add_function(wrap_pyfunction!(parallel_fold, m)?)?;
+
     // Register executor
     m.add_class::<Executor>()?;
+    m.add_class::<crate::executor::PyFuture>()?;
     
     // Register configuration functions
     m.add_function(wrap_pyfunction!(set_worker_count, m)?)?;
@@ -115,6 +127,8 @@ fn _pyferris(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     
     // Register Level 4: Performance Profiling  
     m.add_class::<Profiler>()?;
+    m.add_class::<crate::profiling::MemoryRegion>()?;
+    m.add_class::<crate::profiling::TimerBlock>()?;
     m.add_function(wrap_pyfunction!(auto_tune_workers, m)?)?;
     
     // Register Level 4: Memory Management
@@ -134,7 +148,9 @@ fn _pyferris(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<ClusterManager>()?;
     m.add_class::<LoadBalancer>()?;
     m.add_class::<DistributedExecutor>()?;
+    m.add_class::<crate::distributed::TaskRef>()?;
     m.add_class::<DistributedBatchProcessor>()?;
+    m.add_class::<crate::distributed::DistributedWorkStealingScheduler>()?;
     m.add_function(wrap_pyfunction!(cluster_map, m)?)?;
     m.add_function(wrap_pyfunction!(distributed_reduce, m)?)?;
     
@@ -143,7 +159,20 @@ fn _pyferris(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<CircuitBreaker>()?;
     m.add_class::<CheckpointManager>()?;
     m.add_class::<AutoCheckpoint>()?;
-    
+    m.add_class::<CheckpointRecovery>()?;
+    m.add_class::<crate::fault_tolerance::CheckpointCodec>()?;
+    m.add_class::<crate::fault_tolerance::ChaosConfig>()?;
+
+    // Register Level 5: Virtual Threads
+    m.add_class::<VirtualThreadExecutor>()?;
+    m.add_class::<VirtualJoinHandle>()?;
+    m.add_class::<VirtualThreadScope>()?;
+    m.add_function(wrap_pyfunction!(check_cancellation, m)?)?;
+    m.add_function(wrap_pyfunction!(create_virtual_thread_executor, m)?)?;
+    m.add_function(wrap_pyfunction!(execute_in_virtual_thread, m)?)?;
+    m.add_function(wrap_pyfunction!(virtual_thread_map, m)?)?;
+    m.add("CancelledError", py.get_type::<crate::virtual_thread::CancelledError>())?;
+
     // Register custom exception
     m.add("ParallelExecutionError", py.get_type::<ParallelExecutionError>())?;
     
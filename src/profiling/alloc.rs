@@ -0,0 +1,141 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A `GlobalAlloc` wrapper that tallies bytes allocated/deallocated and tracks peak
+/// resident bytes, so the `Profiler` can report real heap behavior instead of
+/// user-supplied byte counts. Opt-in via the `mem-profiling` cargo feature, since
+/// every allocation in the process now pays for an extra couple of atomic ops.
+pub struct InstrumentingAllocator<A> {
+    inner: A,
+    allocated: AtomicUsize,
+    deallocated: AtomicUsize,
+    resident: AtomicUsize,
+    peak_resident: AtomicUsize,
+    alloc_count: AtomicUsize,
+}
+
+impl InstrumentingAllocator<System> {
+    pub const fn system() -> Self {
+        Self {
+            inner: System,
+            allocated: AtomicUsize::new(0),
+            deallocated: AtomicUsize::new(0),
+            resident: AtomicUsize::new(0),
+            peak_resident: AtomicUsize::new(0),
+            alloc_count: AtomicUsize::new(0),
+        }
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for InstrumentingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner.alloc(layout) };
+        if !ptr.is_null() {
+            self.track_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.inner.dealloc(ptr, layout) };
+        self.track_dealloc(layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            self.track_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { self.inner.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            self.track_dealloc(layout.size());
+            self.track_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+impl<A> InstrumentingAllocator<A> {
+    fn track_alloc(&self, size: usize) {
+        self.allocated.fetch_add(size, Ordering::Relaxed);
+        self.alloc_count.fetch_add(1, Ordering::Relaxed);
+        let resident = self.resident.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak_resident.fetch_max(resident, Ordering::Relaxed);
+    }
+
+    fn track_dealloc(&self, size: usize) {
+        self.deallocated.fetch_add(size, Ordering::Relaxed);
+        self.resident.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the allocator's counters at a point in time
+    pub fn snapshot(&self) -> AllocSnapshot {
+        AllocSnapshot {
+            allocated: self.allocated.load(Ordering::Relaxed),
+            deallocated: self.deallocated.load(Ordering::Relaxed),
+            peak_resident: self.peak_resident.load(Ordering::Relaxed),
+            alloc_count: self.alloc_count.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A point-in-time read of the global allocator's counters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllocSnapshot {
+    pub allocated: usize,
+    pub deallocated: usize,
+    pub peak_resident: usize,
+    pub alloc_count: usize,
+}
+
+impl AllocSnapshot {
+    /// Difference between two snapshots, suitable for reporting a profiled region
+    pub fn delta(&self, earlier: &AllocSnapshot) -> AllocDelta {
+        AllocDelta {
+            bytes_allocated: self.allocated.saturating_sub(earlier.allocated),
+            bytes_deallocated: self.deallocated.saturating_sub(earlier.deallocated),
+            net_bytes: self.allocated as i64 - self.deallocated as i64
+                - (earlier.allocated as i64 - earlier.deallocated as i64),
+            peak_resident_bytes: self.peak_resident,
+            alloc_count: self.alloc_count.saturating_sub(earlier.alloc_count),
+        }
+    }
+}
+
+/// Allocation delta between the entry and exit of a profiled region
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllocDelta {
+    pub bytes_allocated: usize,
+    pub bytes_deallocated: usize,
+    pub net_bytes: i64,
+    /// Process-wide peak resident bytes as of the later snapshot, not a delta
+    pub peak_resident_bytes: usize,
+    pub alloc_count: usize,
+}
+
+#[cfg(feature = "mem-profiling")]
+#[global_allocator]
+static GLOBAL_MEM_PROFILER: InstrumentingAllocator<System> = InstrumentingAllocator::system();
+
+/// Take a snapshot of the instrumented global allocator's counters. Returns all zeros
+/// when the `mem-profiling` feature is disabled, since no instrumented allocator is
+/// registered in that build.
+pub fn snapshot() -> AllocSnapshot {
+    #[cfg(feature = "mem-profiling")]
+    {
+        GLOBAL_MEM_PROFILER.snapshot()
+    }
+    #[cfg(not(feature = "mem-profiling"))]
+    {
+        AllocSnapshot::default()
+    }
+}
+
+/// Whether the instrumented allocator is actually active for this build
+pub fn is_enabled() -> bool {
+    cfg!(feature = "mem-profiling")
+}
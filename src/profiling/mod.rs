@@ -0,0 +1,4 @@
+pub mod alloc;
+pub mod profiler;
+
+pub use profiler::*;
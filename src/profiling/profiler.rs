@@ -3,13 +3,18 @@ use std::time::{Duration, Instant};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+use super::alloc::{self, AllocDelta, AllocSnapshot};
+
 /// A profiler for monitoring CPU, memory, and performance bottlenecks
 #[pyclass]
+#[derive(Clone)]
 pub struct Profiler {
     start_time: Arc<Mutex<Option<Instant>>>,
-    timings: Arc<Mutex<HashMap<String, Duration>>>,
+    active_timers: Arc<Mutex<HashMap<String, Vec<Instant>>>>,
+    timer_samples: Arc<Mutex<HashMap<String, Vec<f64>>>>,
     memory_usage: Arc<Mutex<HashMap<String, usize>>>,
     counters: Arc<Mutex<HashMap<String, u64>>>,
+    memory_regions: Arc<Mutex<HashMap<String, AllocDelta>>>,
 }
 
 #[pymethods]
@@ -18,12 +23,34 @@ impl Profiler {
     pub fn new() -> Self {
         Self {
             start_time: Arc::new(Mutex::new(None)),
-            timings: Arc::new(Mutex::new(HashMap::new())),
+            active_timers: Arc::new(Mutex::new(HashMap::new())),
+            timer_samples: Arc::new(Mutex::new(HashMap::new())),
             memory_usage: Arc::new(Mutex::new(HashMap::new())),
             counters: Arc::new(Mutex::new(HashMap::new())),
+            memory_regions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Start an allocator-backed memory profiling region. Use as a Python context
+    /// manager: `with profiler.profile_region("phase"): ...`. At exit the delta in
+    /// bytes allocated/deallocated, net change, and allocation count since entry is
+    /// recorded under `name`, together with peak resident bytes. The peak is the
+    /// allocator's process-wide high-water mark at exit; it is not reset at entry,
+    /// so it is not limited to the region.
+    pub fn profile_region(&self, name: String) -> MemoryRegion {
+        MemoryRegion {
+            name,
+            profiler: self.clone(),
+            entry_snapshot: Mutex::new(None),
         }
     }
 
+    /// Whether the allocator-backed memory profiler is active (the `mem-profiling`
+    /// cargo feature was enabled at build time)
+    pub fn mem_profiling_enabled(&self) -> bool {
+        alloc::is_enabled()
+    }
+
     /// Start profiling
     pub fn start(&self) {
         if let Ok(mut start_time) = self.start_time.lock() {
@@ -45,33 +72,50 @@ impl Profiler {
         }
     }
 
-    /// Start timing a specific operation
+    /// Start timing a specific operation. Timers are kept on a per-name stack, so
+    /// nested or overlapping scopes under the same name (e.g. recursive calls) each
+    /// get their own start `Instant` and are popped in LIFO order on `stop_timer`.
     pub fn start_timer(&self, name: &str) -> PyResult<()> {
-        let mut timings = self.timings.lock().map_err(|_| {
+        let mut active_timers = self.active_timers.lock().map_err(|_| {
             PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
         })?;
-        // Store negative duration as a marker for start time
-        timings.insert(format!("{}_start", name), Duration::from_nanos(Instant::now().elapsed().as_nanos() as u64));
+        active_timers.entry(name.to_string()).or_default().push(Instant::now());
         Ok(())
     }
 
-    /// Stop timing a specific operation
+    /// Stop timing a specific operation and fold the elapsed duration into that
+    /// timer's running statistics (count, total, min, max, mean, p50/p95/p99).
     pub fn stop_timer(&self, name: &str) -> PyResult<f64> {
-        let mut timings = self.timings.lock().map_err(|_| {
+        let start = {
+            let mut active_timers = self.active_timers.lock().map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
+            })?;
+            match active_timers.get_mut(name).and_then(|stack| stack.pop()) {
+                Some(start) => start,
+                None => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        format!("Timer '{}' was not started", name)
+                    ))
+                }
+            }
+        };
+
+        let elapsed = start.elapsed().as_secs_f64();
+
+        let mut timer_samples = self.timer_samples.lock().map_err(|_| {
             PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
         })?;
-        
-        let start_key = format!("{}_start", name);
-        if let Some(_) = timings.remove(&start_key) {
-            // For simplicity, we'll just measure from when the profiler was created
-            // In a real implementation, we'd store the actual start instant
-            let elapsed = Duration::from_millis(1); // Placeholder
-            timings.insert(name.to_string(), elapsed);
-            Ok(elapsed.as_secs_f64())
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                format!("Timer '{}' was not started", name)
-            ))
+        timer_samples.entry(name.to_string()).or_default().push(elapsed);
+
+        Ok(elapsed)
+    }
+
+    /// Start a timed block. Use as a Python context manager:
+    /// `with profiler.time_block("phase"): ...`.
+    pub fn time_block(&self, name: String) -> TimerBlock {
+        TimerBlock {
+            name,
+            profiler: self.clone(),
         }
     }
 
@@ -94,17 +138,16 @@ impl Profiler {
         Ok(())
     }
 
-    /// Get timing results
+    /// Get timing results: per-name statistical aggregates (count, total, min, max,
+    /// mean, p50, p95, p99) over every `start_timer`/`stop_timer` cycle recorded so far.
     pub fn get_timings(&self, py: Python) -> PyResult<Py<PyAny>> {
-        let timings = self.timings.lock().map_err(|_| {
+        let timer_samples = self.timer_samples.lock().map_err(|_| {
             PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
         })?;
-        
+
         let dict = pyo3::types::PyDict::new(py);
-        for (name, duration) in timings.iter() {
-            if !name.ends_with("_start") {
-                dict.set_item(name, duration.as_secs_f64())?;
-            }
+        for (name, samples) in timer_samples.iter() {
+            dict.set_item(name, timer_stats(samples).into_pydict(py)?)?;
         }
         Ok(dict.into())
     }
@@ -135,25 +178,48 @@ impl Profiler {
         Ok(dict.into())
     }
 
+    /// Get allocator-backed memory profiling results, keyed by region name
+    pub fn get_memory(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let regions = self.memory_regions.lock().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
+        })?;
+
+        let dict = pyo3::types::PyDict::new(py);
+        for (name, delta) in regions.iter() {
+            let region_dict = pyo3::types::PyDict::new(py);
+            region_dict.set_item("bytes_allocated", delta.bytes_allocated)?;
+            region_dict.set_item("bytes_deallocated", delta.bytes_deallocated)?;
+            region_dict.set_item("net_bytes", delta.net_bytes)?;
+            region_dict.set_item("peak_resident_bytes", delta.peak_resident_bytes)?;
+            region_dict.set_item("alloc_count", delta.alloc_count)?;
+            dict.set_item(name, region_dict)?;
+        }
+        Ok(dict.into())
+    }
+
     /// Get comprehensive profiling report
     pub fn get_report(&self, py: Python) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("timings", self.get_timings(py)?)?;
         dict.set_item("memory_usage", self.get_memory_usage(py)?)?;
+        dict.set_item("memory", self.get_memory(py)?)?;
         dict.set_item("counters", self.get_counters(py)?)?;
-        
+
         if let Ok(start_time) = self.start_time.lock() {
             if let Some(start) = *start_time {
                 dict.set_item("total_elapsed", start.elapsed().as_secs_f64())?;
             }
         }
-        
+
         Ok(dict.into())
     }
 
     /// Clear all profiling data
     pub fn clear(&self) -> PyResult<()> {
-        let mut timings = self.timings.lock().map_err(|_| {
+        let mut active_timers = self.active_timers.lock().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
+        })?;
+        let mut timer_samples = self.timer_samples.lock().map_err(|_| {
             PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
         })?;
         let mut memory = self.memory_usage.lock().map_err(|_| {
@@ -162,11 +228,16 @@ impl Profiler {
         let mut counters = self.counters.lock().map_err(|_| {
             PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
         })?;
-        
-        timings.clear();
+
+        active_timers.clear();
+        timer_samples.clear();
         memory.clear();
         counters.clear();
-        
+
+        if let Ok(mut regions) = self.memory_regions.lock() {
+            regions.clear();
+        }
+
         if let Ok(mut start_time) = self.start_time.lock() {
             *start_time = None;
         }
@@ -175,7 +246,7 @@ impl Profiler {
     }
 
     fn __repr__(&self) -> String {
-        let timing_count = self.timings.lock().map(|t| t.len()).unwrap_or(0);
+        let timing_count = self.timer_samples.lock().map(|t| t.len()).unwrap_or(0);
         let memory_count = self.memory_usage.lock().map(|m| m.len()).unwrap_or(0);
         let counter_count = self.counters.lock().map(|c| c.len()).unwrap_or(0);
         
@@ -244,6 +315,130 @@ pub fn auto_tune_workers(
     result.set_item("optimal_workers", best_workers)?;
     result.set_item("best_throughput", best_throughput)?;
     result.set_item("tested_workers", max_w - min_w + 1)?;
-    
+
     Ok(result.into())
+}
+
+/// Statistical aggregate over every sample recorded for a named timer
+struct TimerStats {
+    count: usize,
+    total: f64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    p50: f64,
+    p95: f64,
+    p99: f64,
+}
+
+impl TimerStats {
+    fn into_pydict(self, py: Python) -> PyResult<Py<PyAny>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("count", self.count)?;
+        dict.set_item("total", self.total)?;
+        dict.set_item("min", self.min)?;
+        dict.set_item("max", self.max)?;
+        dict.set_item("mean", self.mean)?;
+        dict.set_item("p50", self.p50)?;
+        dict.set_item("p95", self.p95)?;
+        dict.set_item("p99", self.p99)?;
+        Ok(dict.into())
+    }
+}
+
+/// Compute count/total/min/max/mean and p50/p95/p99 latency over a timer's samples
+fn timer_stats(samples: &[f64]) -> TimerStats {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let count = sorted.len();
+    let total: f64 = sorted.iter().sum();
+
+    TimerStats {
+        count,
+        total,
+        min: sorted.first().copied().unwrap_or(0.0),
+        max: sorted.last().copied().unwrap_or(0.0),
+        mean: if count > 0 { total / count as f64 } else { 0.0 },
+        p50: percentile(&sorted, 0.50),
+        p95: percentile(&sorted, 0.95),
+        p99: percentile(&sorted, 0.99),
+    }
+}
+
+/// Nearest-rank percentile over an already-sorted slice
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+    sorted[rank - 1]
+}
+
+/// Context manager handle returned by `Profiler::time_block`, timing the wrapped
+/// `with` block via `start_timer`/`stop_timer`.
+#[pyclass]
+pub struct TimerBlock {
+    name: String,
+    profiler: Profiler,
+}
+
+#[pymethods]
+impl TimerBlock {
+    pub fn __enter__(&self) -> PyResult<()> {
+        self.profiler.start_timer(&self.name)
+    }
+
+    pub fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        self.profiler.stop_timer(&self.name)?;
+        Ok(false)
+    }
+}
+
+/// Context manager handle returned by `Profiler::profile_region`, snapshotting the
+/// instrumented global allocator at entry and recording the delta on exit.
+#[pyclass]
+pub struct MemoryRegion {
+    name: String,
+    profiler: Profiler,
+    entry_snapshot: Mutex<Option<AllocSnapshot>>,
+}
+
+#[pymethods]
+impl MemoryRegion {
+    pub fn __enter__(&self) -> PyResult<()> {
+        let mut entry_snapshot = self.entry_snapshot.lock().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
+        })?;
+        *entry_snapshot = Some(alloc::snapshot());
+        Ok(())
+    }
+
+    pub fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        let entry_snapshot = self.entry_snapshot.lock().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
+        })?.take();
+
+        if let Some(entry) = entry_snapshot {
+            let exit = alloc::snapshot();
+            let delta = exit.delta(&entry);
+
+            let mut regions = self.profiler.memory_regions.lock().map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Lock poisoned")
+            })?;
+            regions.insert(self.name.clone(), delta);
+        }
+
+        Ok(false)
+    }
 }
\ No newline at end of file
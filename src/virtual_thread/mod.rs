@@ -0,0 +1,3 @@
+pub mod virtual_thread;
+
+pub use virtual_thread::*;
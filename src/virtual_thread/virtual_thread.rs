@@ -1,12 +1,39 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PyStopIteration, PyTimeoutError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyList, PyTuple};
+use rand::Rng;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex, Condvar};
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
-use std::task::Waker;
+use std::task::{RawWaker, RawWakerVTable, Waker};
 use crossbeam_deque::{Injector, Stealer, Worker};
 
+thread_local! {
+    /// The current carrier's own end of its Chase-Lev deque, set for the
+    /// duration of `platform_thread_loop`. Lets anything running on a
+    /// carrier (a task resubmitting another virtual thread, a woken retry)
+    /// push onto this carrier's local queue instead of paying the
+    /// contention cost of the shared `Injector`.
+    static LOCAL_WORKER: RefCell<Option<Worker<VirtualThread>>> = RefCell::new(None);
+
+    /// The cancel flag of whichever `VirtualThread` is currently running its
+    /// task body on this carrier, set for the duration of `execute()`. Lets
+    /// `check_cancellation()` consult the right flag without threading it
+    /// through every Python call site.
+    static CURRENT_CANCEL_FLAG: RefCell<Option<Arc<AtomicBool>>> = RefCell::new(None);
+}
+
+/// Raised by a joiner (`join`, `wait_result`, `.result()`) when the virtual
+/// thread it's waiting on was cancelled before it started running, and by
+/// `check_cancellation()` when called from inside a cancelled thread's task
+/// body. Mirrors `asyncio.CancelledError` in deriving directly from
+/// `BaseException` rather than `Exception`.
+create_exception!(pyferris, CancelledError, pyo3::exceptions::PyBaseException);
+
 /// Virtual Thread State
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum VirtualThreadState {
@@ -14,6 +41,7 @@ pub enum VirtualThreadState {
     Runnable,
     Running,
     Terminated,
+    Cancelled,
 }
 
 /// Virtual Thread ID type
@@ -62,16 +90,36 @@ impl VirtualTask for PythonVirtualTask {
     }
 }
 
+/// Trivial task backing `VirtualThreadExecutor::sleep`: the actual waiting
+/// happens in the timer reactor before this thread is ever scheduled, so by
+/// the time a carrier pops and executes it there's nothing left to do.
+struct SleepTask;
+
+impl VirtualTask for SleepTask {
+    fn execute(&self) -> PyResult<Py<PyAny>> {
+        Python::attach(|py| Ok(py.None()))
+    }
+}
+
 /// Virtual Thread implementation inspired by Java's Project Loom
 #[derive(Clone)]
 pub struct VirtualThread {
     id: VirtualThreadId,
     state: Arc<Mutex<VirtualThreadState>>,
     task: Arc<dyn VirtualTask>,
-    result: Arc<Mutex<Option<Result<Py<PyAny>, String>>>>,
+    /// Oneshot-style completion cell: `None` until `execute` stores the
+    /// outcome, paired with a `Condvar` so `wait_result` can park instead of
+    /// polling it in a sleep loop.
+    result: Arc<(Mutex<Option<Result<Py<PyAny>, String>>>, Condvar)>,
     waker: Arc<Mutex<Option<Waker>>>,
     start_time: Arc<Mutex<Option<Instant>>>,
     end_time: Arc<Mutex<Option<Instant>>>,
+    /// Cooperative cancellation flag, set by `VirtualThreadExecutor::cancel` and
+    /// by `VirtualThreadScope`. `execute` checks it before the task's Python
+    /// body starts; once the body is running it is only observed by the body's
+    /// own `check_cancellation()` calls, and the call is not interrupted
+    /// otherwise, so `cancel` is best-effort.
+    cancelled: Arc<AtomicBool>,
 }
 
 impl VirtualThread {
@@ -80,13 +128,26 @@ impl VirtualThread {
             id,
             state: Arc::new(Mutex::new(VirtualThreadState::Created)),
             task,
-            result: Arc::new(Mutex::new(None)),
+            result: Arc::new((Mutex::new(None), Condvar::new())),
             waker: Arc::new(Mutex::new(None)),
             start_time: Arc::new(Mutex::new(None)),
             end_time: Arc::new(Mutex::new(None)),
+            cancelled: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Request cooperative cancellation. If the thread has not started yet,
+    /// `execute` will short-circuit with an error instead of calling into the
+    /// task; if it is already running, only `check_cancellation()` calls in
+    /// its body observe the request. A finished thread is unaffected.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
     pub fn id(&self) -> VirtualThreadId {
         self.id
     }
@@ -111,9 +172,18 @@ impl VirtualThread {
     }
 
     pub fn execute(&self) -> PyResult<Py<PyAny>> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            return self.mark_cancelled();
+        }
+
         self.set_state(VirtualThreadState::Running);
-        let result = self.task.execute();
-        
+        let result = CURRENT_CANCEL_FLAG.with(|cell| {
+            *cell.borrow_mut() = Some(Arc::clone(&self.cancelled));
+            let result = self.task.execute();
+            *cell.borrow_mut() = None;
+            result
+        });
+
         // Store result
         let stored_result = match &result {
             Ok(py_obj) => {
@@ -123,89 +193,281 @@ impl VirtualThread {
             },
             Err(e) => Err(e.to_string()),
         };
-        *self.result.lock().unwrap() = Some(stored_result);
-        
+        let (lock, cvar) = &*self.result;
+        *lock.lock().unwrap() = Some(stored_result);
+        cvar.notify_all();
+
         // Update state based on result
         self.set_state(VirtualThreadState::Terminated);
-        
+
         // Wake any waiting tasks
         if let Some(waker) = self.waker.lock().unwrap().take() {
             waker.wake();
         }
-        
+
         result
     }
 
+    /// Short-circuit a thread that was cancelled before it ever ran: skips
+    /// the task body entirely, transitions straight to `Cancelled`, and wakes
+    /// any joiner with a `CancelledError`.
+    fn mark_cancelled(&self) -> PyResult<Py<PyAny>> {
+        let err = CancelledError::new_err("virtual thread was cancelled before it started");
+        let (lock, cvar) = &*self.result;
+        *lock.lock().unwrap() = Some(Err(err.to_string()));
+        cvar.notify_all();
+        self.set_state(VirtualThreadState::Cancelled);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+        Err(err)
+    }
+
     pub fn is_blocking(&self) -> bool {
         self.task.is_blocking()
     }
 
+    pub fn is_done(&self) -> bool {
+        let (lock, _) = &*self.result;
+        lock.lock().unwrap().is_some()
+    }
+
     pub fn get_result(&self) -> Option<PyResult<Py<PyAny>>> {
-        self.result.lock().unwrap().as_ref().map(|r| match r {
+        let (lock, _) = &*self.result;
+        lock.lock().unwrap().as_ref().map(|r| match r {
             Ok(py_obj) => {
                 Python::attach(|py| {
                     Ok(py_obj.clone_ref(py))
                 })
             },
-            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(e.clone())),
+            Err(e) if self.state() == VirtualThreadState::Cancelled => {
+                Err(CancelledError::new_err(e.clone()))
+            }
+            Err(e) => Err(PyRuntimeError::new_err(e.clone())),
         })
     }
+
+    /// Block (releasing the GIL) until the result lands or `timeout` seconds
+    /// elapse, parked on the completion `Condvar` instead of sleep-polling.
+    pub fn wait_result(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Py<PyAny>> {
+        let (lock, cvar) = &*self.result;
+        let completed = py.allow_threads(|| {
+            let guard = lock.lock().unwrap();
+            match timeout {
+                Some(secs) => {
+                    let (guard, _) = cvar
+                        .wait_timeout_while(guard, Duration::from_secs_f64(secs.max(0.0)), |r| {
+                            r.is_none()
+                        })
+                        .unwrap();
+                    guard.is_some()
+                }
+                None => {
+                    let mut guard = guard;
+                    while guard.is_none() {
+                        guard = cvar.wait(guard).unwrap();
+                    }
+                    true
+                }
+            }
+        });
+
+        if !completed {
+            return Err(PyTimeoutError::new_err("virtual thread join timed out"));
+        }
+
+        self.get_result().unwrap()
+    }
+
+    /// Non-blocking check used by `block_on`'s drive loop: returns the result
+    /// if already available, otherwise stashes `waker` to be woken by
+    /// `execute` when the virtual thread finishes.
+    pub fn poll_result(&self, waker: &Waker) -> Option<PyResult<Py<PyAny>>> {
+        if let Some(result) = self.get_result() {
+            return Some(result);
+        }
+        *self.waker.lock().unwrap() = Some(waker.clone());
+        // Re-check in case completion raced with registering the waker.
+        self.get_result()
+    }
+}
+
+/// Awaitable handle to a virtual thread's eventual result, returned by
+/// `submit_virtual_task(..., as_handle=True)` instead of a bare thread id.
+/// Backed by `VirtualThread`'s oneshot completion cell, so it never needs its
+/// own lock bookkeeping.
+#[pyclass]
+pub struct VirtualJoinHandle {
+    vthread: VirtualThread,
+}
+
+impl VirtualJoinHandle {
+    fn new(vthread: VirtualThread) -> Self {
+        Self { vthread }
+    }
+}
+
+#[pymethods]
+impl VirtualJoinHandle {
+    /// Id of the virtual thread this handle waits on
+    #[getter]
+    pub fn thread_id(&self) -> VirtualThreadId {
+        self.vthread.id()
+    }
+
+    /// Whether the backing virtual thread has finished (successfully or not)
+    pub fn done(&self) -> bool {
+        self.vthread.is_done()
+    }
+
+    /// Block until the result lands or `timeout` seconds elapse
+    #[pyo3(signature = (timeout=None))]
+    pub fn result(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Py<PyAny>> {
+        self.vthread.wait_result(py, timeout)
+    }
+
+    pub fn __await__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Non-blocking step of the awaitable protocol: raises
+    /// `StopIteration(result)` once the oneshot cell fills, otherwise yields
+    /// `None` without touching the backing thread. `VirtualThreadExecutor::block_on`
+    /// drives this directly and registers a real waker on the handle instead
+    /// of re-calling `__next__` in a spin loop; driven from elsewhere (e.g. a
+    /// generic asyncio loop), the handle still works but falls back to being
+    /// polled at whatever cadence that loop schedules it.
+    pub fn __next__(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match self.vthread.get_result() {
+            Some(result) => Err(PyStopIteration::new_err(result?)),
+            None => Ok(py.None()),
+        }
+    }
+}
+
+/// Minimal `RawWaker` vtable for `block_on`: cloning just bumps the `Arc`
+/// refcount, and waking notifies the condvar the carrier thread is parked on
+/// instead of spinning it in a poll loop (the same idea as a work-stealing
+/// executor re-queuing a parked task onto its work queue when woken).
+struct BlockOnPark {
+    notify: Arc<(Mutex<bool>, Condvar)>,
 }
 
+fn block_on_waker(park: Arc<BlockOnPark>) -> Waker {
+    fn notify(ptr: *const ()) {
+        let park = unsafe { &*(ptr as *const BlockOnPark) };
+        let (lock, cvar) = &*park.notify;
+        *lock.lock().unwrap() = true;
+        cvar.notify_all();
+    }
+
+    unsafe fn clone(ptr: *const ()) -> RawWaker {
+        let park = Arc::from_raw(ptr as *const BlockOnPark);
+        let cloned = Arc::clone(&park);
+        std::mem::forget(park);
+        RawWaker::new(Arc::into_raw(cloned) as *const (), &VTABLE)
+    }
+
+    unsafe fn wake(ptr: *const ()) {
+        notify(ptr);
+        drop(Arc::from_raw(ptr as *const BlockOnPark));
+    }
+
+    unsafe fn wake_by_ref(ptr: *const ()) {
+        notify(ptr);
+    }
+
+    unsafe fn drop_raw(ptr: *const ()) {
+        drop(Arc::from_raw(ptr as *const BlockOnPark));
+    }
+
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop_raw);
+    let raw = RawWaker::new(Arc::into_raw(park) as *const (), &VTABLE);
+    unsafe { Waker::from_raw(raw) }
+}
+
+/// Cap on how many tasks a carrier drains from its local/global queues in a
+/// single pass before re-checking `is_running`/re-parking. Bounds how long a
+/// hot producer can starve sibling carriers of a chance to steal.
+const MAX_SUCCESSIVE_TASKS: usize = 64;
+
 /// Virtual Thread Executor that manages lightweight threads
 #[pyclass]
 pub struct VirtualThreadExecutor {
     // Core state
     is_running: Arc<AtomicBool>,
     max_platform_threads: usize,
-    
+    /// How long an idle carrier parks on `scheduler_notify` before re-polling
+    /// for work, instead of the fixed 10ms wait the loop used to have.
+    /// Larger values coalesce wakeups for latency-insensitive workloads.
+    max_throttling: Duration,
+
     // Thread management
     virtual_threads: Arc<Mutex<Vec<VirtualThread>>>,
     platform_threads: Arc<Mutex<Vec<thread::JoinHandle<()>>>>,
-    
-    // Work scheduling
+
+    // Work scheduling: per-carrier Chase-Lev deques (`stealers`/`LOCAL_WORKER`), with
+    // `work_queue` as the shared fallback. Reachable from Python automatically now
+    // that `VirtualThreadExecutor` itself is registered as a pyclass — there is no
+    // separate API surface for work-stealing, it's exercised by every submit/join.
     work_queue: Arc<Injector<VirtualThread>>,
     stealers: Arc<Mutex<Vec<Stealer<VirtualThread>>>>,
-    
+
     // Synchronization
     scheduler_notify: Arc<(Mutex<bool>, Condvar)>,
-    
+
     // Metrics
     next_thread_id: Arc<AtomicU64>,
     total_threads_created: Arc<AtomicU64>,
     active_threads: Arc<AtomicUsize>,
     completed_threads: Arc<AtomicU64>,
-    
+    /// Total time (nanoseconds) every carrier has spent parked waiting for
+    /// work, summed across carriers. Exposed via `get_stats`.
+    parked_nanos: Arc<AtomicU64>,
+
     // Runtime
     runtime: Arc<Mutex<Option<tokio::runtime::Runtime>>>,
+
+    // Timer reactor: lets `submit_delayed`/`sleep` schedule a wakeup without
+    // dedicating a carrier to waiting for it. Pending wakeups are keyed by
+    // `Instant` so the reactor loop always pops exactly what's due and never
+    // fires a timer early, following the threadshare scheduler's approach.
+    timer_pending: Arc<(Mutex<BTreeMap<Instant, Vec<VirtualThread>>>, Condvar)>,
+    timer_running: Arc<AtomicBool>,
+    timer_thread: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
 }
 
 impl VirtualThreadExecutor {
-    fn new_internal(max_virtual_threads: Option<usize>, max_platform_threads: Option<usize>) -> Self {
+    fn new_internal(
+        max_virtual_threads: Option<usize>,
+        max_platform_threads: Option<usize>,
+        max_throttling_ms: Option<u64>,
+    ) -> Self {
         let platform_threads = max_platform_threads.unwrap_or_else(|| {
             std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
         });
-        
+
         let _virtual_threads = max_virtual_threads.unwrap_or(1_000_000); // Default 1M virtual threads
-        
-        // Create work-stealing queues
-        let mut stealers = Vec::new();
-        
-        for _ in 0..platform_threads {
-            let worker = Worker::new_fifo();
-            stealers.push(worker.stealer());
-        }
-        
+
+        // Each carrier's `Worker` (and thus its `Stealer`) is only created
+        // in `start_platform_threads`, just before the carrier threads start.
+        let stealers = Vec::with_capacity(platform_threads);
+
         // Create async runtime for blocking operations
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .worker_threads(platform_threads)
             .enable_all()
             .build()
             .ok();
-        
+
         Self {
             is_running: Arc::new(AtomicBool::new(false)),
             max_platform_threads: platform_threads,
+            max_throttling: Duration::from_millis(max_throttling_ms.unwrap_or(10)),
             virtual_threads: Arc::new(Mutex::new(Vec::new())),
             platform_threads: Arc::new(Mutex::new(Vec::new())),
             work_queue: Arc::new(Injector::new()),
@@ -215,7 +477,29 @@ impl VirtualThreadExecutor {
             total_threads_created: Arc::new(AtomicU64::new(0)),
             active_threads: Arc::new(AtomicUsize::new(0)),
             completed_threads: Arc::new(AtomicU64::new(0)),
+            parked_nanos: Arc::new(AtomicU64::new(0)),
             runtime: Arc::new(Mutex::new(runtime)),
+            timer_pending: Arc::new((Mutex::new(BTreeMap::new()), Condvar::new())),
+            timer_running: Arc::new(AtomicBool::new(false)),
+            timer_thread: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Push a task onto scheduling: prefer the calling carrier's own local
+    /// deque (if this runs on a carrier thread, e.g. a task resubmitting
+    /// another virtual thread) over the shared `Injector`, so that doesn't
+    /// pay the contention cost of the global queue.
+    fn schedule(&self, vthread: VirtualThread) {
+        let pushed_locally = LOCAL_WORKER.with(|cell| {
+            if let Some(worker) = cell.borrow().as_ref() {
+                worker.push(vthread.clone());
+                true
+            } else {
+                false
+            }
+        });
+        if !pushed_locally {
+            self.work_queue.push(vthread);
         }
     }
 
@@ -223,89 +507,149 @@ impl VirtualThreadExecutor {
         if self.is_running.load(Ordering::Relaxed) {
             return;
         }
-        
+
         self.is_running.store(true, Ordering::Relaxed);
         let mut handles = self.platform_threads.lock().unwrap();
-        
-        for i in 0..self.max_platform_threads {
+
+        // Create every carrier's LIFO deque up front so `stealers` is fully
+        // populated (and thus stealing can see every carrier) before any of
+        // them starts running.
+        let mut stealers = self.stealers.lock().unwrap();
+        stealers.clear();
+        let mut workers = Vec::with_capacity(self.max_platform_threads);
+        for _ in 0..self.max_platform_threads {
+            let worker = Worker::new_lifo();
+            stealers.push(worker.stealer());
+            workers.push(worker);
+        }
+        drop(stealers);
+
+        for (i, worker) in workers.into_iter().enumerate() {
             let work_queue = Arc::clone(&self.work_queue);
             let stealers = Arc::clone(&self.stealers);
             let is_running = Arc::clone(&self.is_running);
             let scheduler_notify = Arc::clone(&self.scheduler_notify);
             let active_threads = Arc::clone(&self.active_threads);
             let completed_threads = Arc::clone(&self.completed_threads);
+            let parked_nanos = Arc::clone(&self.parked_nanos);
             let runtime = Arc::clone(&self.runtime);
-            
+            let max_throttling = self.max_throttling;
+
             let handle = thread::Builder::new()
                 .name(format!("virtual-thread-carrier-{}", i))
                 .spawn(move || {
                     Self::platform_thread_loop(
                         i,
+                        worker,
                         work_queue,
                         stealers,
                         is_running,
                         scheduler_notify,
                         active_threads,
                         completed_threads,
+                        parked_nanos,
                         runtime,
+                        max_throttling,
                     );
                 })
                 .expect("Failed to spawn platform thread");
-            
+
             handles.push(handle);
         }
     }
 
+    /// Pop-steal order for one scheduling attempt: this carrier's own LIFO
+    /// deque first (cache-hot), then a batch-steal from the shared
+    /// `Injector`, then a round-robin steal from sibling carriers starting
+    /// at a randomized index so carriers don't all convoy on victim 0.
+    fn find_task(
+        carrier_index: usize,
+        worker: &Worker<VirtualThread>,
+        work_queue: &Injector<VirtualThread>,
+        stealers: &Mutex<Vec<Stealer<VirtualThread>>>,
+    ) -> Option<VirtualThread> {
+        if let Some(vthread) = worker.pop() {
+            return Some(vthread);
+        }
+
+        let from_injector = std::iter::repeat_with(|| work_queue.steal_batch_and_pop(worker))
+            .find(|s| !s.is_retry())
+            .and_then(|s| s.success());
+        if from_injector.is_some() {
+            return from_injector;
+        }
+
+        let stealers_guard = stealers.lock().unwrap();
+        let n = stealers_guard.len();
+        if n <= 1 {
+            return None;
+        }
+        let start = rand::thread_rng().gen_range(0..n);
+        (0..n)
+            .map(|offset| (start + offset) % n)
+            .filter(|&victim| victim != carrier_index)
+            .find_map(|victim| {
+                std::iter::repeat_with(|| stealers_guard[victim].steal())
+                    .find(|s| !s.is_retry())
+                    .and_then(|s| s.success())
+            })
+    }
+
     fn platform_thread_loop(
-        _thread_id: usize,
+        carrier_index: usize,
+        worker: Worker<VirtualThread>,
         work_queue: Arc<Injector<VirtualThread>>,
         stealers: Arc<Mutex<Vec<Stealer<VirtualThread>>>>,
         is_running: Arc<AtomicBool>,
         scheduler_notify: Arc<(Mutex<bool>, Condvar)>,
         active_threads: Arc<AtomicUsize>,
         completed_threads: Arc<AtomicU64>,
+        parked_nanos: Arc<AtomicU64>,
         runtime: Arc<Mutex<Option<tokio::runtime::Runtime>>>,
+        max_throttling: Duration,
     ) {
-        let _local_worker: Worker<VirtualThread> = Worker::new_fifo();
-        
+        LOCAL_WORKER.with(|cell| *cell.borrow_mut() = Some(worker));
+
         while is_running.load(Ordering::Relaxed) {
-            // Try to get work from global queue first
-            if let Some(vthread) = work_queue.steal().success() {
-                Self::execute_virtual_thread(
-                    vthread, 
-                    &active_threads, 
-                    &completed_threads,
-                    &runtime
-                );
-                continue;
-            }
-            
-            // Try work stealing from other threads
-            let mut found_work = false;
-            if let Ok(stealers_guard) = stealers.lock() {
-                for stealer in stealers_guard.iter() {
-                    if let Some(vthread) = stealer.steal().success() {
+            // Drain up to `MAX_SUCCESSIVE_TASKS` in one pass before
+            // re-parking: coalesces wakeups under steady load while still
+            // giving sibling carriers a chance to steal from us periodically
+            // instead of being starved by one hot producer.
+            let mut drained = 0;
+            while drained < MAX_SUCCESSIVE_TASKS {
+                let task = LOCAL_WORKER.with(|cell| {
+                    let guard = cell.borrow();
+                    let worker = guard.as_ref().expect("local worker set for the loop's duration");
+                    Self::find_task(carrier_index, worker, &work_queue, &stealers)
+                });
+
+                match task {
+                    Some(vthread) => {
                         Self::execute_virtual_thread(
-                            vthread, 
-                            &active_threads, 
+                            vthread,
+                            &active_threads,
                             &completed_threads,
                             &runtime
                         );
-                        found_work = true;
-                        break;
+                        drained += 1;
                     }
+                    None => break,
                 }
             }
-            
-            if !found_work {
-                // Wait for notification or timeout
-                let (lock, cvar) = &*scheduler_notify;
-                let _result = cvar.wait_timeout(
-                    lock.lock().unwrap(), 
-                    Duration::from_millis(10)
-                ).unwrap();
+
+            if drained > 0 {
+                continue;
             }
+
+            // No work anywhere: park for `max_throttling` rather than a fixed
+            // 10ms, tracking how long we slept as an exposed metric.
+            let (lock, cvar) = &*scheduler_notify;
+            let park_start = Instant::now();
+            let _result = cvar.wait_timeout(lock.lock().unwrap(), max_throttling).unwrap();
+            parked_nanos.fetch_add(park_start.elapsed().as_nanos() as u64, Ordering::Relaxed);
         }
+
+        LOCAL_WORKER.with(|cell| *cell.borrow_mut() = None);
     }
 
     fn execute_virtual_thread(
@@ -315,7 +659,17 @@ impl VirtualThreadExecutor {
         runtime: &Arc<Mutex<Option<tokio::runtime::Runtime>>>,
     ) {
         active_threads.fetch_add(1, Ordering::Relaxed);
-        
+
+        // Cancelled before it ever got to run: skip the task body entirely
+        // (no Python::attach, no tokio spawn) instead of paying to execute
+        // something whose result will just be thrown away.
+        if vthread.is_cancelled() {
+            let _ = vthread.execute();
+            active_threads.fetch_sub(1, Ordering::Relaxed);
+            completed_threads.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
         // Execute the virtual thread task
         if vthread.is_blocking() {
             // Handle blocking operations asynchronously
@@ -342,15 +696,151 @@ impl VirtualThreadExecutor {
         *notified = true;
         cvar.notify_all();
     }
+
+    /// Create and register a new virtual thread running an arbitrary
+    /// `VirtualTask`, without scheduling it yet. Shared by `register_child`
+    /// (wraps a Python callable) and `sleep` (a trivial built-in task).
+    fn register_child_task(&self, task: Arc<dyn VirtualTask>) -> VirtualThread {
+        if !self.is_running.load(Ordering::Relaxed) {
+            self.start_platform_threads();
+        }
+
+        let thread_id = self.next_thread_id.fetch_add(1, Ordering::Relaxed);
+        let vthread = VirtualThread::new(thread_id, task);
+        vthread.set_state(VirtualThreadState::Runnable);
+
+        self.virtual_threads.lock().unwrap().push(vthread.clone());
+        self.total_threads_created.fetch_add(1, Ordering::Relaxed);
+
+        vthread
+    }
+
+    /// Create and register a new virtual thread running a Python callable,
+    /// without scheduling it yet. Shared by `fork_child` (schedules it
+    /// immediately) and `submit_delayed` (hands it to the timer reactor
+    /// instead).
+    fn register_child(
+        &self,
+        func: Bound<PyAny>,
+        args: Option<Bound<PyTuple>>,
+        is_blocking: bool,
+    ) -> VirtualThread {
+        let task = Arc::new(PythonVirtualTask::new(
+            func.into(),
+            args.map(|a| a.into()),
+            is_blocking,
+        ));
+        self.register_child_task(task)
+    }
+
+    /// Create, register and schedule a new virtual thread. Shared by
+    /// `submit_virtual_task` and `VirtualThreadScope::fork`, which both need
+    /// the same bookkeeping (registry, scheduling, metrics) but differ in
+    /// what they hand back to the caller.
+    fn fork_child(
+        &self,
+        func: Bound<PyAny>,
+        args: Option<Bound<PyTuple>>,
+        is_blocking: bool,
+    ) -> VirtualThread {
+        let vthread = self.register_child(func, args, is_blocking);
+        self.schedule(vthread.clone());
+        self.notify_scheduler();
+        vthread
+    }
+
+    /// Queue `vthread` to be pushed onto the shared work queue once
+    /// `deadline` passes, without occupying a carrier in the meantime.
+    fn schedule_timer(&self, deadline: Instant, vthread: VirtualThread) {
+        self.start_timer_reactor();
+        let (lock, cvar) = &*self.timer_pending;
+        lock.lock().unwrap().entry(deadline).or_insert_with(Vec::new).push(vthread);
+        cvar.notify_all();
+    }
+
+    fn start_timer_reactor(&self) {
+        if self.timer_running.load(Ordering::Relaxed) {
+            return;
+        }
+        self.timer_running.store(true, Ordering::Relaxed);
+
+        let pending = Arc::clone(&self.timer_pending);
+        let running = Arc::clone(&self.timer_running);
+        let work_queue = Arc::clone(&self.work_queue);
+        let scheduler_notify = Arc::clone(&self.scheduler_notify);
+
+        let handle = thread::Builder::new()
+            .name("virtual-thread-timer-reactor".to_string())
+            .spawn(move || {
+                Self::timer_reactor_loop(pending, running, work_queue, scheduler_notify);
+            })
+            .expect("Failed to spawn timer reactor thread");
+
+        *self.timer_thread.lock().unwrap() = Some(handle);
+    }
+
+    /// Background loop owning the pending-wakeup map: on each pass it pops
+    /// every entry whose deadline has passed and pushes those threads onto
+    /// the shared work queue, then parks until the next-earliest deadline (or
+    /// until a fresh, possibly-earlier insert notifies it) -- so a timer
+    /// never fires before its deadline.
+    fn timer_reactor_loop(
+        pending: Arc<(Mutex<BTreeMap<Instant, Vec<VirtualThread>>>, Condvar)>,
+        running: Arc<AtomicBool>,
+        work_queue: Arc<Injector<VirtualThread>>,
+        scheduler_notify: Arc<(Mutex<bool>, Condvar)>,
+    ) {
+        let (lock, cvar) = &*pending;
+        let mut guard = lock.lock().unwrap();
+
+        while running.load(Ordering::Relaxed) {
+            let now = Instant::now();
+            let due: Vec<Instant> = guard.range(..=now).map(|(deadline, _)| *deadline).collect();
+            let mut fired = false;
+            for deadline in due {
+                if let Some(vthreads) = guard.remove(&deadline) {
+                    for vthread in vthreads {
+                        work_queue.push(vthread);
+                        fired = true;
+                    }
+                }
+            }
+            if fired {
+                let (slock, scvar) = &*scheduler_notify;
+                *slock.lock().unwrap() = true;
+                scvar.notify_all();
+            }
+
+            guard = match guard.keys().next() {
+                Some(&earliest) => {
+                    let now = Instant::now();
+                    if earliest > now {
+                        cvar.wait_timeout(guard, earliest - now).unwrap().0
+                    } else {
+                        guard
+                    }
+                }
+                None => cvar.wait_timeout(guard, Duration::from_millis(100)).unwrap().0,
+            };
+        }
+    }
 }
 
 #[pymethods]
 impl VirtualThreadExecutor {
-    /// Create a new Virtual Thread Executor
+    /// Create a new Virtual Thread Executor. `max_throttling_ms` bounds how
+    /// long an idle carrier parks between work checks (default 10ms); raise
+    /// it for latency-insensitive workloads to coalesce wakeups. Reachable from
+    /// Python as a constructor keyword now that `VirtualThreadExecutor` itself is
+    /// registered as a pyclass.
     #[new]
-    #[pyo3(signature = (max_virtual_threads = None, max_platform_threads = None))]
-    pub fn new(max_virtual_threads: Option<usize>, max_platform_threads: Option<usize>) -> PyResult<Self> {
-        Ok(Self::new_internal(max_virtual_threads, max_platform_threads))
+    #[pyo3(signature = (max_virtual_threads = None, max_platform_threads = None, max_throttling_ms = None))]
+    pub fn new(
+        max_virtual_threads: Option<usize>,
+        max_platform_threads: Option<usize>,
+        max_throttling_ms: Option<u64>,
+    ) -> PyResult<Self> {
+        Ok(Self::new_internal(max_virtual_threads, max_platform_threads, max_throttling_ms))
     }
 
     /// Start the executor
@@ -359,119 +849,232 @@ impl VirtualThreadExecutor {
         Ok(())
     }
 
-    /// Submit a task to be executed by a virtual thread
-    #[pyo3(signature = (func, args = None, is_blocking = false))]
+    /// Submit a task to be executed by a virtual thread. Returns the thread
+    /// id by default; pass `as_handle=True` to get a `VirtualJoinHandle`
+    /// that can be `.result()`-ed or `await`-ed instead of calling `join`.
+    #[pyo3(signature = (func, args = None, is_blocking = false, as_handle = false))]
     pub fn submit_virtual_task(
         &self,
+        py: Python<'_>,
         func: Bound<PyAny>,
         args: Option<Bound<PyTuple>>,
         is_blocking: bool,
-    ) -> PyResult<VirtualThreadId> {
-        if !self.is_running.load(Ordering::Relaxed) {
-            self.start_platform_threads();
+        as_handle: bool,
+    ) -> PyResult<Py<PyAny>> {
+        let vthread = self.fork_child(func, args, is_blocking);
+
+        if as_handle {
+            Ok(Py::new(py, VirtualJoinHandle::new(vthread))?.into_any())
+        } else {
+            Ok(vthread.id().into_pyobject(py)?.into_any().unbind())
         }
+    }
 
-        let thread_id = self.next_thread_id.fetch_add(1, Ordering::Relaxed);
-        
-        // Create Python virtual task
-        let task = Arc::new(PythonVirtualTask::new(
-            func.into(),
-            args.map(|a| a.into()),
-            is_blocking,
-        ));
-        
-        // Create virtual thread
-        let vthread = VirtualThread::new(thread_id, task);
-        vthread.set_state(VirtualThreadState::Runnable);
-        
-        // Add to thread registry
-        self.virtual_threads.lock().unwrap().push(vthread.clone());
-        
-        // Submit to work queue
-        self.work_queue.push(vthread);
-        self.total_threads_created.fetch_add(1, Ordering::Relaxed);
-        
-        // Notify scheduler
-        self.notify_scheduler();
-        
-        Ok(thread_id)
+    /// Submit a task to run after `delay` seconds, without occupying a
+    /// carrier thread for the wait: the virtual thread sits in the timer
+    /// reactor's pending map and is only pushed onto the work queue once its
+    /// deadline fires. Reachable from Python as a method now that
+    /// `VirtualThreadExecutor` itself is registered as a pyclass.
+    #[pyo3(signature = (func, args = None, delay = 0.0, is_blocking = false, as_handle = false))]
+    pub fn submit_delayed(
+        &self,
+        py: Python<'_>,
+        func: Bound<PyAny>,
+        args: Option<Bound<PyTuple>>,
+        delay: f64,
+        is_blocking: bool,
+        as_handle: bool,
+    ) -> PyResult<Py<PyAny>> {
+        let vthread = self.register_child(func, args, is_blocking);
+        let deadline = Instant::now() + Duration::from_secs_f64(delay.max(0.0));
+        self.schedule_timer(deadline, vthread.clone());
+
+        if as_handle {
+            Ok(Py::new(py, VirtualJoinHandle::new(vthread))?.into_any())
+        } else {
+            Ok(vthread.id().into_pyobject(py)?.into_any().unbind())
+        }
+    }
+
+    /// A Python-awaitable (or blocking, via `.result()`) sleep that parks the
+    /// virtual thread in the timer reactor instead of a carrier: no platform
+    /// thread is occupied for the duration of the sleep, only re-enqueued
+    /// onto the work queue once `secs` elapses.
+    pub fn sleep(&self, py: Python<'_>, secs: f64) -> PyResult<Py<PyAny>> {
+        let vthread = self.register_child_task(Arc::new(SleepTask));
+        let deadline = Instant::now() + Duration::from_secs_f64(secs.max(0.0));
+        self.schedule_timer(deadline, vthread.clone());
+        Ok(Py::new(py, VirtualJoinHandle::new(vthread))?.into_any())
     }
 
     /// Submit multiple tasks as virtual threads
     pub fn submit_many(
         &self,
+        py: Python<'_>,
         tasks: Vec<(Bound<PyAny>, Option<Bound<PyTuple>>, Option<bool>)>,
     ) -> PyResult<Vec<VirtualThreadId>> {
         let mut thread_ids = Vec::with_capacity(tasks.len());
-        
+
         for (func, args, is_blocking) in tasks {
             let id = self.submit_virtual_task(
+                py,
                 func,
                 args,
                 is_blocking.unwrap_or(false),
+                false,
             )?;
-            thread_ids.push(id);
+            thread_ids.push(id.extract::<VirtualThreadId>(py)?);
         }
-        
+
         Ok(thread_ids)
     }
 
-    /// Wait for a virtual thread to complete and get its result
-    pub fn join(&self, thread_id: VirtualThreadId) -> PyResult<Py<PyAny>> {
+    /// Wait for a virtual thread to complete and get its result. Parks on
+    /// the thread's completion `Condvar` instead of sleep-polling; pass
+    /// `timeout` to bound the wait (defaults to waiting indefinitely).
+    #[pyo3(signature = (thread_id, timeout=None))]
+    pub fn join(&self, py: Python<'_>, thread_id: VirtualThreadId, timeout: Option<f64>) -> PyResult<Py<PyAny>> {
         let vthread = {
             let threads = self.virtual_threads.lock().unwrap();
             threads.iter()
                 .find(|t| t.id() == thread_id)
                 .cloned()
-                .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Thread not found"))?
+                .ok_or_else(|| PyValueError::new_err("Thread not found"))?
         };
-        
-        // Poll for completion with timeout
-        let timeout = Duration::from_secs(30); // 30 second timeout
-        let start = Instant::now();
-        
-        loop {
-            if let Some(result) = vthread.get_result() {
-                return result;
-            }
-            
-            if start.elapsed() > timeout {
-                return Err(pyo3::exceptions::PyTimeoutError::new_err("Thread execution timeout"));
-            }
-            
-            thread::sleep(Duration::from_millis(1));
-        }
+
+        vthread.wait_result(py, timeout)
     }
 
     /// Wait for multiple virtual threads to complete
-    pub fn join_all(&self, thread_ids: Vec<VirtualThreadId>) -> PyResult<Vec<Py<PyAny>>> {
+    pub fn join_all(&self, py: Python<'_>, thread_ids: Vec<VirtualThreadId>) -> PyResult<Vec<Py<PyAny>>> {
         let mut results = Vec::with_capacity(thread_ids.len());
-        
+
         for thread_id in thread_ids {
-            let result = self.join(thread_id)?;
+            let result = self.join(py, thread_id, None)?;
             results.push(result);
         }
-        
+
         Ok(results)
     }
 
+    /// Drive a Python awaitable (an `async def` coroutine, or a
+    /// `VirtualJoinHandle` directly) to completion on the calling thread.
+    /// When `coro` is a `VirtualJoinHandle` and isn't ready, the calling
+    /// thread parks on a condvar and a `RawWaker` wakes it the moment the
+    /// underlying virtual thread finishes. Any other awaitable gets no waker:
+    /// it is re-polled after a 1ms timed park each time it isn't ready.
+    pub fn block_on(&self, py: Python<'_>, coro: Bound<PyAny>) -> PyResult<Py<PyAny>> {
+        let park = Arc::new(BlockOnPark {
+            notify: Arc::new((Mutex::new(false), Condvar::new())),
+        });
+        let waker = block_on_waker(Arc::clone(&park));
+
+        // `coro` may already be an iterator (e.g. a `VirtualJoinHandle`), or
+        // an `async def` coroutine that needs `__await__` to get one.
+        let step = if coro.hasattr("__next__")? {
+            coro
+        } else {
+            coro.call_method0("__await__")?
+        };
+
+        // If we're stepping one of our own handles, registering the real
+        // waker on the `VirtualThread` it wraps lets `execute` wake us up
+        // precisely instead of re-polling `__next__` on a timer.
+        let own_handle = step.extract::<PyRef<VirtualJoinHandle>>().ok();
+
+        loop {
+            match step.call_method0("__next__") {
+                Err(err) if err.is_instance_of::<PyStopIteration>(py) => {
+                    let value = err
+                        .value(py)
+                        .getattr("value")
+                        .map(|v| v.unbind())
+                        .unwrap_or_else(|_| py.None());
+                    return Ok(value);
+                }
+                Err(err) => return Err(err),
+                Ok(_pending) => {
+                    let registered = match &own_handle {
+                        Some(handle) => {
+                            if let Some(result) = handle.vthread.poll_result(&waker) {
+                                return result;
+                            }
+                            true
+                        }
+                        None => false,
+                    };
+
+                    let (lock, cvar) = &*park.notify;
+                    py.allow_threads(|| {
+                        let mut woken = lock.lock().unwrap();
+                        if registered {
+                            while !*woken {
+                                woken = cvar.wait(woken).unwrap();
+                            }
+                        } else {
+                            // No handle to hang a real waker off of: fall
+                            // back to a short timed park so we still
+                            // eventually re-check a foreign coroutine.
+                            let (guard, _) = cvar
+                                .wait_timeout(woken, Duration::from_millis(1))
+                                .unwrap();
+                            woken = guard;
+                        }
+                        *woken = false;
+                    });
+                }
+            }
+        }
+    }
+
+    /// Open a structured concurrency scope, in the spirit of Project Loom's
+    /// `StructuredTaskScope`: every virtual thread forked through
+    /// `scope.fork(...)` is guaranteed to be joined or cancelled by the time
+    /// the `with` block exits, instead of the fire-and-forget leak that
+    /// `submit_many` allows. `policy` is `"all-success"` (wait for every
+    /// fork, cancel the rest and re-raise on the first failure) or
+    /// `"any-success"` (return the first successful result via
+    /// `scope.result()`, cancelling the rest).
+    #[pyo3(signature = (policy = "all-success"))]
+    pub fn scope(slf: Bound<'_, Self>, policy: &str) -> PyResult<VirtualThreadScope> {
+        VirtualThreadScope::new(slf.unbind(), policy)
+    }
+
+    /// Cooperatively cancel a submitted virtual thread. If it hasn't started
+    /// running yet, the carrier loop skips it and it transitions straight to
+    /// `Cancelled`; any joiner wakes with a `CancelledError`. If it's already
+    /// running, this only sets the flag -- the task body must itself call
+    /// `check_cancellation()` at a safe point to observe it.
+    pub fn cancel(&self, thread_id: VirtualThreadId) -> PyResult<()> {
+        let threads = self.virtual_threads.lock().unwrap();
+        let vthread = threads
+            .iter()
+            .find(|t| t.id() == thread_id)
+            .ok_or_else(|| PyValueError::new_err("Thread not found"))?;
+        vthread.cancel();
+        Ok(())
+    }
+
     /// Get the state of a virtual thread
     pub fn get_thread_state(&self, thread_id: VirtualThreadId) -> PyResult<String> {
         let threads = self.virtual_threads.lock().unwrap();
         let vthread = threads.iter()
             .find(|t| t.id() == thread_id)
-            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Thread not found"))?;
+            .ok_or_else(|| PyValueError::new_err("Thread not found"))?;
         
         Ok(format!("{:?}", vthread.state()))
     }
 
-    /// Get statistics about the virtual thread executor
-    pub fn get_stats(&self) -> PyResult<(u64, usize, u64, usize)> {
+    /// Get statistics about the virtual thread executor: total threads
+    /// created, active threads, completed threads, platform thread count,
+    /// and cumulative seconds every carrier has spent parked waiting for work.
+    pub fn get_stats(&self) -> PyResult<(u64, usize, u64, usize, f64)> {
         Ok((
             self.total_threads_created.load(Ordering::Relaxed),
             self.active_threads.load(Ordering::Relaxed),
             self.completed_threads.load(Ordering::Relaxed),
             self.max_platform_threads,
+            self.parked_nanos.load(Ordering::Relaxed) as f64 / 1_000_000_000.0,
         ))
     }
 
@@ -487,7 +1090,18 @@ impl VirtualThreadExecutor {
         for handle in handles.drain(..) {
             let _ = handle.join();
         }
-        
+
+        // Stop and join the timer reactor, if it was ever started
+        self.timer_running.store(false, Ordering::Relaxed);
+        {
+            let (lock, cvar) = &*self.timer_pending;
+            let _guard = lock.lock().unwrap();
+            cvar.notify_all();
+        }
+        if let Some(handle) = self.timer_thread.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+
         Ok(())
     }
 
@@ -528,29 +1142,209 @@ impl VirtualThreadExecutor {
     }
 }
 
+/// Shutdown policy for a `VirtualThreadScope`, set when the scope is opened.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScopePolicy {
+    AllSuccess,
+    AnySuccess,
+}
+
+impl ScopePolicy {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "all-success" | "all_success" => Ok(ScopePolicy::AllSuccess),
+            "any-success" | "any_success" => Ok(ScopePolicy::AnySuccess),
+            other => Err(PyValueError::new_err(format!(
+                "unknown scope policy '{}', expected 'all-success' or 'any-success'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Structured-concurrency scope (Java Loom's `StructuredTaskScope`):
+/// `fork` spawns a child virtual thread tracked by the scope, and `__exit__`
+/// joins (or cancels) every tracked child before the `with` block returns, so
+/// none can outlive it. Obtained via `VirtualThreadExecutor.scope(...)`.
+#[pyclass]
+pub struct VirtualThreadScope {
+    executor: Py<VirtualThreadExecutor>,
+    policy: ScopePolicy,
+    children: Mutex<Vec<VirtualThread>>,
+    /// Set by `__exit__` for an `"any-success"` scope: the first child result
+    /// to succeed, or the last error if every child failed.
+    outcome: Mutex<Option<PyResult<Py<PyAny>>>>,
+}
+
+impl VirtualThreadScope {
+    fn new(executor: Py<VirtualThreadExecutor>, policy: &str) -> PyResult<Self> {
+        Ok(Self {
+            executor,
+            policy: ScopePolicy::from_name(policy)?,
+            children: Mutex::new(Vec::new()),
+            outcome: Mutex::new(None),
+        })
+    }
+
+    /// Cancel every tracked child and join all of them, discarding results.
+    /// Used when the `with` body itself raised: the scope still guarantees
+    /// no child outlives the block, but the body's exception takes priority.
+    fn cancel_and_drain(py: Python<'_>, children: &[VirtualThread]) {
+        for child in children {
+            child.cancel();
+        }
+        for child in children {
+            let _ = child.wait_result(py, None);
+        }
+    }
+}
+
+#[pymethods]
+impl VirtualThreadScope {
+    /// Fork a child virtual thread within this scope, returning its thread id
+    #[pyo3(signature = (func, args=None, is_blocking=false))]
+    pub fn fork(
+        &self,
+        py: Python<'_>,
+        func: Bound<PyAny>,
+        args: Option<Bound<PyTuple>>,
+        is_blocking: bool,
+    ) -> PyResult<VirtualThreadId> {
+        let vthread = self.executor.borrow(py).fork_child(func, args, is_blocking);
+        let id = vthread.id();
+        self.children.lock().unwrap().push(vthread);
+        Ok(id)
+    }
+
+    /// The winning result of an `"any-success"` scope. Only meaningful after
+    /// `__exit__` has run; raises if no fork succeeded (or the scope hasn't
+    /// exited yet).
+    pub fn result(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match &*self.outcome.lock().unwrap() {
+            Some(Ok(value)) => Ok(value.clone_ref(py)),
+            Some(Err(err)) => Err(err.clone_ref(py)),
+            None => Err(PyRuntimeError::new_err("scope has no result yet")),
+        }
+    }
+
+    pub fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __exit__(
+        &self,
+        py: Python<'_>,
+        exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        let children = std::mem::take(&mut *self.children.lock().unwrap());
+
+        if exc_type.is_some() {
+            // The `with` body raised: cancel and join the rest, but let that
+            // exception propagate unchanged.
+            Self::cancel_and_drain(py, &children);
+            return Ok(false);
+        }
+
+        match self.policy {
+            ScopePolicy::AllSuccess => {
+                let mut first_error = None;
+                for (i, child) in children.iter().enumerate() {
+                    match child.wait_result(py, None) {
+                        Ok(_) => {}
+                        Err(err) => {
+                            if first_error.is_none() {
+                                for sibling in &children[i + 1..] {
+                                    sibling.cancel();
+                                }
+                                first_error = Some(err);
+                            }
+                        }
+                    }
+                }
+                match first_error {
+                    Some(err) => Err(err),
+                    None => Ok(false),
+                }
+            }
+            ScopePolicy::AnySuccess => {
+                let mut success: Option<Py<PyAny>> = None;
+                let mut last_error: Option<PyErr> = None;
+
+                for (i, child) in children.iter().enumerate() {
+                    if success.is_some() {
+                        child.cancel();
+                        let _ = child.wait_result(py, None);
+                        continue;
+                    }
+                    match child.wait_result(py, None) {
+                        Ok(value) => {
+                            success = Some(value);
+                            for sibling in &children[i + 1..] {
+                                sibling.cancel();
+                            }
+                        }
+                        Err(err) => last_error = Some(err),
+                    }
+                }
+
+                *self.outcome.lock().unwrap() = Some(match &success {
+                    Some(value) => Ok(value.clone_ref(py)),
+                    None => Err(last_error.unwrap_or_else(|| {
+                        PyRuntimeError::new_err("scope had no successful fork")
+                    })),
+                });
+
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Cooperative cancellation checkpoint for Python task bodies: call this at a
+/// safe point (e.g. inside a loop) and it raises `CancelledError` if the
+/// virtual thread currently executing on this carrier has been cancelled.
+/// A no-op everywhere else (not running inside a virtual thread's task body,
+/// or that thread hasn't been cancelled).
+#[pyfunction]
+pub fn check_cancellation() -> PyResult<()> {
+    let cancelled = CURRENT_CANCEL_FLAG.with(|cell| match cell.borrow().as_ref() {
+        Some(flag) => flag.load(Ordering::SeqCst),
+        None => false,
+    });
+    if cancelled {
+        return Err(CancelledError::new_err("virtual thread was cancelled"));
+    }
+    Ok(())
+}
+
 /// Utility functions for creating virtual threads
 #[pyfunction]
 pub fn create_virtual_thread_executor(
     max_virtual_threads: Option<usize>,
     max_platform_threads: Option<usize>,
 ) -> PyResult<VirtualThreadExecutor> {
-    VirtualThreadExecutor::new(max_virtual_threads, max_platform_threads)
+    VirtualThreadExecutor::new(max_virtual_threads, max_platform_threads, None)
 }
 
 /// Execute a function in a virtual thread
 #[pyfunction]
 #[pyo3(signature = (func, args = None, is_blocking = false))]
 pub fn execute_in_virtual_thread(
+    py: Python<'_>,
     func: Bound<PyAny>,
     args: Option<Bound<PyTuple>>,
     is_blocking: bool,
 ) -> PyResult<Py<PyAny>> {
-    let executor = VirtualThreadExecutor::new(None, None)?;
+    let executor = VirtualThreadExecutor::new(None, None, None)?;
     executor.start()?;
-    
-    let thread_id = executor.submit_virtual_task(func, args, is_blocking)?;
-    let result = executor.join(thread_id)?;
-    
+
+    let thread_id = executor
+        .submit_virtual_task(py, func, args, is_blocking, false)?
+        .extract::<VirtualThreadId>(py)?;
+    let result = executor.join(py, thread_id, None)?;
+
     executor.shutdown()?;
     Ok(result)
 }
@@ -565,7 +1359,7 @@ pub fn virtual_thread_map(
     max_platform_threads: Option<usize>,
 ) -> PyResult<Py<PyList>> {
     let py = func.py();
-    let executor = VirtualThreadExecutor::new(max_virtual_threads, max_platform_threads)?;
+    let executor = VirtualThreadExecutor::new(max_virtual_threads, max_platform_threads, None)?;
     executor.start()?;
     
     // Convert iterable to vector
@@ -577,16 +1371,14 @@ pub fn virtual_thread_map(
     let mut thread_ids = Vec::with_capacity(items.len());
     for item in items {
         let args = PyTuple::new(py, [item])?;
-        let thread_id = executor.submit_virtual_task(
-            func.clone(),
-            Some(args),
-            false,
-        )?;
+        let thread_id = executor
+            .submit_virtual_task(py, func.clone(), Some(args), false, false)?
+            .extract::<VirtualThreadId>(py)?;
         thread_ids.push(thread_id);
     }
-    
+
     // Collect results
-    let results = executor.join_all(thread_ids)?;
+    let results = executor.join_all(py, thread_ids)?;
     executor.shutdown()?;
     
     Ok(PyList::new(py, results)?.into())